@@ -1,49 +1,196 @@
-use crate::{ops::*, set::{self, *, Cardinality}, util::*};
+use crate::{ops::*, set::{self, *, Cardinality}, util::*, greens_relations::GreensRelations, error::Error};
 use std::rc::Rc;
+use std::hash::Hash;
+use std::collections::HashSet;
 use std::marker::PhantomData;
 
 
 pub trait HasSet<T> {
     fn get_set(&self) -> Rc<dyn SetT<T>>;
     
-    fn is_subset_of(&self, other: &dyn HasSet<T>) -> bool {
-        let set = self.get_set();
+    // None when the set cannot be iterated
+    fn is_subset_of(&self, other: &dyn HasSet<T>) -> Option<bool> {
         let other_set = other.get_set();
-        unimplemented!("Implement this function")
+        Some(self.get_set().iter()?.all(|x| other_set.contains(&x)))
     }
 }
 
 pub trait GroupLike<O: OpFlag, T>: HasSet<T> + Sized
 where T: Op<O>
 {
-    fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, &'static str>;
+    fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, Error<T>>;
     fn new_unchecked(set: Rc<dyn SetT<T>>) -> Self;
-    fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, &'static str>;
+    fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> where T: Hash + Eq;
 }
 
 
-use magma::*;
-use semigroup::*;
-use monoid::*;
 use group::*;
 
-pub use group::{Group, GroupT};
+// How many powers of an element are tried before giving up, when the set does not bound it
+pub const POWER_SEARCH_LIMIT: usize = 1 << 20;
+
+// MARK: AXIOMS
+// Each check runs over finite iterable sets only, sets that cannot be iterated are trusted to satisfy the axiom
+
+pub fn check_closure<O: OpFlag, T>(set: &dyn SetT<T>) -> Result<(), Error<T>>
+where T: Op<O> + PartialEq + Clone
+{
+    let Some(elements) = set.iter() else { return Ok(()) };
+    let elements: Vec<T> = elements.collect();
+    for a in &elements {
+        for b in &elements {
+            if !set.contains(&a.op(b.clone())) {
+                return Err(Error::NotClosed { a: a.clone(), b: b.clone() });
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn check_associativity<O: OpFlag, T>(set: &dyn SetT<T>) -> Result<(), Error<T>>
+where T: Op<O> + PartialEq + Clone
+{
+    let Some(elements) = set.iter() else { return Ok(()) };
+    let elements: Vec<T> = elements.collect();
+    for a in &elements {
+        for b in &elements {
+            let ab = a.op(b.clone());
+            for c in &elements {
+                if ab.op(c.clone()) != a.op(b.op(c.clone())) {
+                    return Err(Error::NotAssociative { a: a.clone(), b: b.clone(), c: c.clone() });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Every row and column of the multiplication table is a permutation of the set, checked through Op alone
+pub fn check_latin_square<O: OpFlag, T>(set: &dyn SetT<T>) -> Result<(), Error<T>>
+where T: Op<O> + PartialEq + Clone
+{
+    let Some(elements) = set.iter() else { return Ok(()) };
+    let elements: Vec<T> = elements.collect();
+    for a in &elements {
+        for b in &elements {
+            let right_solutions = elements.iter().filter(|x| a.op((*x).clone()) == *b).count();
+            let left_solutions = elements.iter().filter(|y| y.op(a.clone()) == *b).count();
+            if right_solutions != 1 || left_solutions != 1 {
+                return Err(Error::NotDivisible { a: a.clone(), b: b.clone() });
+            }
+        }
+    }
+    Ok(())
+}
+
+// The Divisibility implementation agrees with the operation and stays inside the set
+pub fn check_divisibility<O: OpFlag, T>(set: &dyn SetT<T>) -> Result<(), Error<T>>
+where T: Divisibility<O> + PartialEq + Clone
+{
+    let Some(elements) = set.iter() else { return Ok(()) };
+    let elements: Vec<T> = elements.collect();
+    for a in &elements {
+        for b in &elements {
+            if !divides(set, a, b) {
+                return Err(Error::NotDivisible { a: a.clone(), b: b.clone() });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn divides<O: OpFlag, T>(set: &dyn SetT<T>, a: &T, b: &T) -> bool
+where T: Divisibility<O> + PartialEq + Clone
+{
+    match (a.left_div(b.clone()), b.right_div(a.clone())) {
+        (Some(x), Some(y)) => set.contains(&x) && set.contains(&y) && a.op(x) == *b && y.op(a.clone()) == *b,
+        _ => false,
+    }
+}
+
+pub fn check_identity<O: OpFlag, T>(set: &dyn SetT<T>) -> Result<(), Error<T>>
+where T: Identity<O> + PartialEq + Clone
+{
+    let identity = T::identity();
+    let Some(elements) = set.iter() else {
+        return if set.contains(&identity) { Ok(()) } else { Err(Error::MissingIdentity) };
+    };
+
+    // The laws are checked with the set's own copy of the identity, a formal identity would satisfy them trivially
+    let elements: Vec<T> = elements.collect();
+    let e = elements.iter().find(|&x| *x == identity).ok_or(Error::MissingIdentity)?;
+    for x in &elements {
+        if e.op(x.clone()) != *x || x.op(e.clone()) != *x {
+            return Err(Error::IdentityFails { element: x.clone() });
+        }
+    }
+    Ok(())
+}
+
+pub fn check_inverses<O: OpFlag, T>(set: &dyn SetT<T>) -> Result<(), Error<T>>
+where T: Inverse<O> + Identity<O> + PartialEq + Clone
+{
+    let Some(elements) = set.iter() else { return Ok(()) };
+    let e = T::identity();
+    for x in elements {
+        let Some(inv) = x.try_inverse() else { return Err(Error::NotInvertible { element: x }) };
+        if !set.contains(&inv) || x.op(inv.clone()) != e || inv.op(x.clone()) != e {
+            return Err(Error::NotInvertible { element: x });
+        }
+    }
+    Ok(())
+}
+
+// Everything reachable from seed by multiplying on the right by generators, only terminates for finite closures
+fn close_under_op<O: OpFlag, T>(generators: &[T], seed: Vec<T>) -> Vec<T>
+where T: Op<O> + Hash + Eq + Clone
+{
+    let mut seen: HashSet<T> = HashSet::new();
+    let mut elements: Vec<T> = Vec::new();
+    for x in seed {
+        if seen.insert(x.clone()) {
+            elements.push(x);
+        }
+    }
+
+    let mut i = 0;
+    while i < elements.len() {
+        for g in generators {
+            let y = elements[i].op(g.clone());
+            if seen.insert(y.clone()) {
+                elements.push(y);
+            }
+        }
+        i += 1;
+    }
+    elements
+}
+
+pub use magma::{Magma, MagmaT, MagmaElement};
+pub use semigroup::{Semigroup, SemigroupT, SemigroupElement};
+pub use quasigroup::{Quasigroup, QuasigroupT, QuasigroupElement};
+pub use loop_::{Loop, LoopT, LoopElement};
+pub use monoid::{Monoid, MonoidT, MonoidElement};
+pub use group::{Group, GroupT, GroupElement};
 
 // MARK: MAGMA
 mod magma {
     use super::*;
 
+    pub trait MagmaElement<O: OpFlag> = Op<O> + PartialEq + Clone + 'static;
+
     pub struct Magma<O: OpFlag, T>
-    where T: Op<O>
+    where T: MagmaElement<O>
     {
         set: Rc<dyn SetT<T>>,
         _boo: PhantomData<O>
     }
 
     impl<O: OpFlag, T> GroupLike<O, T> for Magma<O, T>
-    where T: Op<O>
+    where T: MagmaElement<O>
     {
-        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
+        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
+            check_closure(set.as_ref())?;
             Ok(Magma { set, _boo: PhantomData })
         }
 
@@ -51,33 +198,33 @@ mod magma {
             Magma { set, _boo: PhantomData }
         }
 
-        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
+        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
             unimplemented!();
         }
     }
 
     pub trait MagmaT<O: OpFlag, T>: HasSet<T>
-    where T: Op<O>
+    where T: MagmaElement<O>
     {
         fn as_magma(&self) -> Magma<O, T> {
             Magma::new_unchecked(self.get_set())
         }
 
-        fn is_submagma_of(&self, other: &dyn MagmaT<O, T>) -> bool {
+        fn is_submagma_of(&self, other: &dyn MagmaT<O, T>) -> Option<bool> {
             // Reasoning: Both are closed under the same operation, so A subset of B implies A is a submagma of B
             self.is_subset_of(other)
         }
     }
     
     impl<O: OpFlag, T> HasSet<T> for Magma<O, T> 
-    where T: Op<O>
+    where T: MagmaElement<O>
     {
         fn get_set(&self) -> Rc<dyn SetT<T>> {
             self.set.clone()
         }
     }
     
-    impl<O: OpFlag, T> MagmaT<O, T> for Magma<O, T> where T: Op<O> {}
+    impl<O: OpFlag, T> MagmaT<O, T> for Magma<O, T> where T: MagmaElement<O> {}
 }
 
 
@@ -85,7 +232,7 @@ mod magma {
 mod semigroup {
     use super::*;
 
-    pub trait SemigroupElement<O: OpFlag> = Associative<O> + PartialEq;
+    pub trait SemigroupElement<O: OpFlag> = Associative<O> + PartialEq + Clone + 'static;
 
     pub struct Semigroup<O: OpFlag, T>
     where T: SemigroupElement<O>
@@ -97,7 +244,9 @@ mod semigroup {
     impl<O: OpFlag, T> GroupLike<O, T> for Semigroup<O, T>
     where T: SemigroupElement<O>
     {
-        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
+        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
+            check_closure(set.as_ref())?;
+            check_associativity(set.as_ref())?;
             Ok(Semigroup { set, _boo: PhantomData })
         }
 
@@ -105,8 +254,10 @@ mod semigroup {
             Semigroup { set, _boo: PhantomData }
         }
 
-        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
-            unimplemented!();
+        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> where T: Hash + Eq {
+            let generators: Vec<T> = generators.iter().ok_or(Error::NotIterable)?.collect();
+            let elements = close_under_op(&generators, generators.clone());
+            Ok(Semigroup { set: Rc::new(SmallSet::new(elements)), _boo: PhantomData })
         }
     }
 
@@ -117,10 +268,79 @@ mod semigroup {
             Semigroup::new_unchecked(self.get_set())
         }
 
-        fn is_subsemigroup_of(&self, other: &dyn SemigroupT<O, T>) -> bool {
+        fn is_subsemigroup_of(&self, other: &dyn SemigroupT<O, T>) -> Option<bool> {
             // Reasoning: Both are closed under the same operation, so A subset of B implies A is a subsemigroup of B
             self.is_subset_of(other)
         }
+
+        // Smallest (index, period) with x^(index + period) = x^index, None if no power repeats within the search limit
+        // Brent's cycle detection, so only a couple of powers are kept around at a time
+        fn index_and_period(&self, x: T) -> Option<(usize, usize)> {
+            let limit = match self.get_set().cardinality() {
+                Cardinality::Finite(n) => n + 1,
+                Cardinality::Infinite => POWER_SEARCH_LIMIT,
+            };
+            let next = |y: &T| y.op(x.clone());
+
+            // The phase whose power first reaches both the index and the period finds the cycle,
+            // and both are at most the limit, so a phase past twice the limit means no power repeats
+            let (mut power, mut period) = (1, 1);
+            let mut tortoise = x.clone();
+            let mut hare = next(&x);
+            while tortoise != hare {
+                if power == period {
+                    if power > 2 * limit {
+                        return None;
+                    }
+                    tortoise = hare.clone();
+                    power *= 2;
+                    period = 0;
+                }
+                hare = next(&hare);
+                period += 1;
+            }
+
+            let mut tortoise = x.clone();
+            let mut hare = x.clone();
+            for _ in 0..period {
+                hare = next(&hare);
+            }
+            let mut index = 1;
+            while tortoise != hare {
+                tortoise = next(&tortoise);
+                hare = next(&hare);
+                index += 1;
+            }
+            Some((index, period))
+        }
+
+        // The unique idempotent among the powers of x
+        fn idempotent_power(&self, x: T) -> Option<T> {
+            let (index, period) = self.index_and_period(x.clone())?;
+            let k = index.div_ceil(period) * period;
+            let mut res = x.clone();
+            for _ in 1..k {
+                res = res.op(x.clone());
+            }
+            Some(res)
+        }
+
+        // Only available when the set can be iterated and is closed under the operation
+        fn greens_relations(&self) -> Option<GreensRelations<T>> where T: Hash + Eq {
+            GreensRelations::new::<O>(self.get_set().iter()?.collect()).ok()
+        }
+
+        fn is_regular(&self) -> Option<bool> where T: Hash + Eq {
+            Some(self.greens_relations()?.is_regular())
+        }
+
+        fn is_inverse_semigroup(&self) -> Option<bool> where T: Hash + Eq {
+            Some(self.greens_relations()?.is_inverse_semigroup())
+        }
+
+        fn is_band(&self) -> Option<bool> where T: Hash + Eq {
+            Some(self.greens_relations()?.is_band())
+        }
     }
 
     impl<O: OpFlag, T> HasSet<T> for Semigroup<O, T> 
@@ -140,7 +360,7 @@ mod semigroup {
 mod quasigroup {
     use super::*;
 
-    pub trait QuasigroupElement<O: OpFlag> = Inverse<O> + PartialEq;
+    pub trait QuasigroupElement<O: OpFlag> = Divisibility<O> + PartialEq + Clone + 'static;
 
     pub struct Quasigroup<O: OpFlag, T>
     where T: QuasigroupElement<O>
@@ -152,7 +372,10 @@ mod quasigroup {
     impl<O: OpFlag, T> GroupLike<O, T> for Quasigroup<O, T>
     where T: QuasigroupElement<O>
     {
-        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
+        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
+            check_closure(set.as_ref())?;
+            check_latin_square(set.as_ref())?;
+            check_divisibility(set.as_ref())?;
             Ok(Quasigroup { set, _boo: PhantomData })
         }
 
@@ -160,7 +383,7 @@ mod quasigroup {
             Quasigroup { set, _boo: PhantomData }
         }
 
-        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
+        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
             unimplemented!();
         }
     }
@@ -168,17 +391,19 @@ mod quasigroup {
     pub trait QuasigroupT<O: OpFlag, T>: MagmaT<O, T>
     where T: QuasigroupElement<O>
     {
+        // Both a \ b and b / a exist in the set and solve their equations
         fn test_divisibility(&self, a: T, b: T) -> bool {
-            unimplemented!();
+            divides(self.get_set().as_ref(), &a, &b)
         }
 
         fn as_quasigroup(&self) -> Quasigroup<O, T> {
             Quasigroup::new_unchecked(self.get_set())
         }
 
-        fn is_subquasigroup_of(&self, other: &dyn QuasigroupT<O, T>) -> bool {
-            unimplemented!();
-            self.is_subset_of(other)
+        // Unlike for magmas, a subset also has to be closed under both divisions
+        fn is_subquasigroup_of(&self, other: &dyn QuasigroupT<O, T>) -> Option<bool> {
+            let elements: Vec<T> = self.get_set().iter()?.collect();
+            Some(self.is_subset_of(other)? && elements.iter().all(|a| elements.iter().all(|b| self.test_divisibility(a.clone(), b.clone()))))
         }
     }
 
@@ -195,11 +420,121 @@ mod quasigroup {
 }
 
 
+// MARK: LOOP
+mod loop_ {
+    use super::*;
+
+    pub trait LoopElement<O: OpFlag> = Divisibility<O> + Identity<O> + PartialEq + Clone + 'static;
+
+    pub struct Loop<O: OpFlag, T>
+    where T: LoopElement<O>
+    {
+        set: Rc<dyn SetT<T>>,
+        _boo: PhantomData<O>
+    }
+
+    impl<O: OpFlag, T> GroupLike<O, T> for Loop<O, T>
+    where T: LoopElement<O>
+    {
+        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
+            check_closure(set.as_ref())?;
+            check_latin_square(set.as_ref())?;
+            check_divisibility(set.as_ref())?;
+            check_identity(set.as_ref())?;
+            Ok(Loop { set, _boo: PhantomData })
+        }
+
+        fn new_unchecked(set: Rc<dyn SetT<T>>) -> Self {
+            Loop { set, _boo: PhantomData }
+        }
+
+        // Without associativity every pair has to be multiplied and divided, not just words in the generators
+        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> where T: Hash + Eq {
+            let generators: Vec<T> = generators.iter().ok_or(Error::NotIterable)?.collect();
+            let mut seen: HashSet<T> = HashSet::new();
+            let mut elements: Vec<T> = Vec::new();
+            for x in std::iter::once(T::identity()).chain(generators) {
+                if seen.insert(x.clone()) {
+                    elements.push(x);
+                }
+            }
+
+            let mut i = 0;
+            while i < elements.len() {
+                for j in 0..=i {
+                    let (a, b) = (elements[i].clone(), elements[j].clone());
+                    let results = [
+                        Some(a.op(b.clone())), Some(b.op(a.clone())),
+                        a.left_div(b.clone()), b.left_div(a.clone()),
+                        a.right_div(b.clone()), b.right_div(a.clone()),
+                    ];
+                    for y in results.into_iter().flatten() {
+                        if seen.insert(y.clone()) {
+                            elements.push(y);
+                        }
+                    }
+                }
+                i += 1;
+            }
+            Ok(Loop { set: Rc::new(SmallSet::new(elements)), _boo: PhantomData })
+        }
+    }
+
+    // Checks that an identity in three variables holds for every x, y, z in the set
+    fn holds_for_all<T: Clone>(elements: &[T], identity: impl Fn(&T, &T, &T) -> bool) -> bool {
+        elements.iter().all(|x| elements.iter().all(|y| elements.iter().all(|z| identity(x, y, z))))
+    }
+
+    pub trait LoopT<O: OpFlag, T>: QuasigroupT<O, T>
+    where T: LoopElement<O>
+    {
+        fn as_loop(&self) -> Loop<O, T> {
+            Loop::new_unchecked(self.get_set())
+        }
+
+        fn is_subloop_of(&self, other: &dyn LoopT<O, T>) -> Option<bool> {
+            Some(self.get_set().contains(&T::identity()) && self.is_subquasigroup_of(other)?)
+        }
+
+        // x(y(xz)) = (x(yx))z, None when the set cannot be iterated
+        fn is_left_bol(&self) -> Option<bool> {
+            let elements: Vec<T> = self.get_set().iter()?.collect();
+            let m = |a: &T, b: &T| a.op(b.clone());
+            Some(holds_for_all(&elements, |x, y, z| m(x, &m(y, &m(x, z))) == m(&m(x, &m(y, x)), z)))
+        }
+
+        // ((zx)y)x = z((xy)x), None when the set cannot be iterated
+        fn is_right_bol(&self) -> Option<bool> {
+            let elements: Vec<T> = self.get_set().iter()?.collect();
+            let m = |a: &T, b: &T| a.op(b.clone());
+            Some(holds_for_all(&elements, |x, y, z| m(&m(&m(z, x), y), x) == m(z, &m(&m(x, y), x))))
+        }
+
+        // A loop is Moufang exactly when it is both left and right Bol
+        fn is_moufang(&self) -> Option<bool> {
+            Some(self.is_left_bol()? && self.is_right_bol()?)
+        }
+    }
+
+    impl<O: OpFlag, T> HasSet<T> for Loop<O, T> 
+    where T: LoopElement<O>
+    {
+        fn get_set(&self) -> Rc<dyn SetT<T>> {
+            self.set.clone()
+        }
+    }
+
+    impl<O: OpFlag, T> MagmaT<O, T> for Loop<O, T> where T: LoopElement<O> {}
+    impl<O: OpFlag, T> QuasigroupT<O, T> for Loop<O, T> where T: LoopElement<O> {}
+    impl<O: OpFlag, T> LoopT<O, T> for Loop<O, T> where T: LoopElement<O> {}
+}
+
+
 // MARK: MONOID
 mod monoid {
     use super::*;
 
-    pub trait MonoidElement<O: OpFlag> = Identity<O> + Associative<O> + PartialEq;
+    pub trait MonoidElement<O: OpFlag> = Identity<O> + Associative<O> + PartialEq + Clone + 'static;
 
     pub struct Monoid<O: OpFlag, T>
     where T: MonoidElement<O>
@@ -211,8 +546,10 @@ mod monoid {
     impl<O: OpFlag, T> GroupLike<O, T> for Monoid<O, T>
     where T: MonoidElement<O>
     {
-        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
-            assert!(set.contains(&T::identity()));
+        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
+            check_closure(set.as_ref())?;
+            check_associativity(set.as_ref())?;
+            check_identity(set.as_ref())?;
             Ok(Monoid { set, _boo: PhantomData })
         }
 
@@ -220,8 +557,10 @@ mod monoid {
             Monoid { set, _boo: PhantomData }
         }
 
-        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
-            unimplemented!();
+        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> where T: Hash + Eq {
+            let generators: Vec<T> = generators.iter().ok_or(Error::NotIterable)?.collect();
+            let elements = close_under_op(&generators, vec![T::identity()]);
+            Ok(Monoid { set: Rc::new(SmallSet::new(elements)), _boo: PhantomData })
         }
     }
 
@@ -233,8 +572,8 @@ mod monoid {
             Monoid::new_unchecked(self.get_set())
         }
 
-        fn is_submonoid_of(&self, other: &dyn MonoidT<O, T>) -> bool {
-            other.get_set().contains(&T::identity()) && self.is_submagma_of(other)
+        fn is_submonoid_of(&self, other: &dyn MonoidT<O, T>) -> Option<bool> {
+            Some(other.get_set().contains(&T::identity()) && self.is_submagma_of(other)?)
         }  
     }
 
@@ -256,7 +595,7 @@ mod monoid {
 mod group {
     use super::*;
 
-    pub trait GroupElement<O:OpFlag> = Inverse<O> + Identity<O> + Associative<O> + PartialEq + Clone;
+    pub trait GroupElement<O:OpFlag> = Inverse<O> + Identity<O> + Associative<O> + PartialEq + Clone + 'static;
 
     pub struct Group<O: OpFlag, T>
     where T: GroupElement<O>
@@ -268,8 +607,11 @@ mod group {
     impl<O: OpFlag, T> GroupLike<O, T> for Group<O, T>
     where T: GroupElement<O>
     {
-        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
-            assert!(set.contains(&T::identity()));
+        fn try_new(set: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
+            check_closure(set.as_ref())?;
+            check_associativity(set.as_ref())?;
+            check_identity(set.as_ref())?;
+            check_inverses(set.as_ref())?;
             Ok(Group { set, _boo: PhantomData })
         }
 
@@ -277,7 +619,7 @@ mod group {
             Group { set, _boo: PhantomData }
         }
 
-        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, &'static str> {
+        fn new_from_generators(generators: Rc<dyn SetT<T>>) -> Result<Self, Error<T>> {
             unimplemented!();
         }
     }
@@ -289,7 +631,7 @@ mod group {
             Group::new_unchecked(self.get_set())
         }
 
-        fn is_subgroup_of(&self, other: &dyn GroupT<O, T>) -> bool {
+        fn is_subgroup_of(&self, other: &dyn GroupT<O, T>) -> Option<bool> {
             self.is_submonoid_of(other)
         }
 
@@ -297,14 +639,10 @@ mod group {
             self.get_set().cardinality()
         }
 
-        fn order_of_element(&self, element: T) -> usize {
-            let mut cur = element.clone();
-            let mut count = 1;
-            while cur != T::identity() {
-                cur = cur.op(element.clone());
-                count += 1;
-            }
-            count
+        // In a group the powers of x cycle back through the identity, so the order is the period
+        // None when no power repeats within the search limit, the order may be infinite or just too large to find
+        fn order_of_element(&self, element: T) -> Option<usize> {
+            self.index_and_period(element).map(|(_, period)| period)
         }
     }
 
@@ -337,26 +675,74 @@ mod tests {
         use std::collections::HashSet;
         use std::iter::FromIterator;
 
-        impl Inverse<ADD> for u8 {
+        #[test]
+        fn test_order() {
+            use crate::mod_ints::U32Mod;
+            let G = Group::<ADD, U32Mod<256>>::new_unchecked(Rc::new(UniversalSet::new(Cardinality::Finite(256))));
+            
+            assert!(G.order_of_element(U32Mod(0)) == Some(1));
+            assert!(G.order_of_element(U32Mod(128)) == Some(2));
+            assert!(G.order_of_element(U32Mod(1)) == Some(256));
+            assert!(G.order_of_element(U32Mod(2)) == Some(128));
+            assert!(G.order_of_element(U32Mod(3)) == Some(256));
+            assert!(G.order_of_element(U32Mod(4)) == Some(64));
+
+            // Orders that are not powers of two, where the cycle is only found late in a phase
+            let z9 = Group::<ADD, U32Mod<9>>::new_unchecked(Rc::new(UniversalSet::new(Cardinality::Finite(9))));
+            assert_eq!(z9.order_of_element(U32Mod(1)), Some(9));
+            assert_eq!(z9.order_of_element(U32Mod(3)), Some(3));
+            assert_eq!(z9.idempotent_power(U32Mod(1)), Some(U32Mod(0)));
+            let z100 = Group::<ADD, U32Mod<100>>::new_unchecked(Rc::new(UniversalSet::new(Cardinality::Finite(100))));
+            assert_eq!(z100.order_of_element(U32Mod(1)), Some(100));
+            assert_eq!(z100.order_of_element(U32Mod(15)), Some(20));
+            let z257 = Group::<ADD, U32Mod<257>>::new_unchecked(Rc::new(UniversalSet::new(Cardinality::Finite(257))));
+            assert_eq!(z257.order_of_element(U32Mod(1)), Some(257));
+            let z33 = Group::<ADD, U32Mod<33>>::new_unchecked(Rc::new(UniversalSet::new(Cardinality::Finite(33))));
+            assert_eq!(z33.order_of_element(U32Mod(1)), Some(33));
+        }
+
+        impl Inverse<ADD> for i64 {
             fn inverse(&self) -> Self {
-                0 - *self
+                -*self
             }
         }
-        
 
         #[test]
-        fn test_order() {
- 
-            let G = Group::<ADD, u8>::new_unchecked(Rc::new(UniversalSet::new(Cardinality::Finite(256))));
-            let H = Group::<ADD, u8>::new_unchecked(Rc::new(SmallSet::new(vec![0,128])));
-            
-            
-            assert!(G.order_of_element(0) == 1);
-            assert!(G.order_of_element(128) == 2);
-            assert!(G.order_of_element(1) == 256);
-            assert!(G.order_of_element(2) == 128);
-            assert!(G.order_of_element(3) == 256);
-            assert!(G.order_of_element(4) == 64);
+        fn test_infinite_order() {
+            let integers = Group::<ADD, i64>::new_unchecked(Rc::new(UniversalSet::new(Cardinality::Infinite)));
+            assert!(integers.order_of_element(0) == Some(1));
+            assert!(integers.order_of_element(1).is_none());
+            assert_eq!(integers.is_subgroup_of(&integers), None);
+        }
+    }
+
+    mod test_axioms {
+        use super::*;
+        use crate::error::Error;
+        use crate::permutations::Permutation;
+
+        fn p(cycles: Vec<Vec<usize>>) -> Permutation<3> {
+            Permutation::new_from_disjoint(cycles)
+        }
+
+        #[test]
+        fn test_group_axioms() {
+            let id = p(vec![]);
+            let swap = p(vec![vec![1, 2]]);
+            let rotation = p(vec![vec![1, 2, 3]]);
+
+            let set = SmallSet::new(vec![id.clone(), swap.clone()]);
+            assert!(Group::<OP<1>, _>::try_new(Rc::new(set)).is_ok());
+
+            let set = SmallSet::new(vec![swap.clone()]);
+            assert!(matches!(Group::<OP<1>, _>::try_new(Rc::new(set)), Err(Error::NotClosed { .. })));
+
+            let set = SmallSet::new(vec![id.clone(), rotation.clone()]);
+            let err = Monoid::<OP<1>, _>::try_new(Rc::new(set)).err().unwrap();
+            assert_eq!(err, Error::NotClosed { a: rotation.clone(), b: rotation.clone() });
+
+            let set = SmallSet::new(vec![p(vec![vec![1, 2]]), p(vec![vec![1, 3]]), p(vec![vec![2, 3]])]);
+            assert!(matches!(Magma::<OP<1>, _>::try_new(Rc::new(set)), Err(Error::NotClosed { .. })));
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use crate::{ops::*, set::Cardinality, algebraic_objects::*, mod_ints::*, random::Rng};
+use crate::number_theory::{factorize_u64, carmichael_lambda_u64};
+
+// Elements the solvers work with, Hash + Eq for the baby step table and the rho partition
+pub trait LogElement<O: OpFlag> = GroupElement<O> + Copy + Eq + Hash;
+
+// x^e by squaring, with x^0 the identity
+// The exponent is a u128 so group orders of any modulus fit, whatever the width of usize
+pub fn pow<O: OpFlag, T: LogElement<O>>(mut x: T, mut e: u128) -> T {
+    let mut acc = T::identity();
+    while e > 0 {
+        if e & 1 == 1 {
+            acc = Op::<O>::op(&acc, x);
+        }
+        x = Op::<O>::op(&x, x);
+        e >>= 1;
+    }
+    acc
+}
+
+// MARK: Order
+// Order of g given some multiple n of it (g^n = 1) and the factorization of n
+pub fn order_from_multiple<O: OpFlag, T: LogElement<O>>(g: T, n: u64, factors: &[(u64, u32)]) -> u64 {
+    let mut order = n;
+    for &(q, _) in factors {
+        while order.is_multiple_of(q) && pow(g, (order / q) as u128) == T::identity() {
+            order /= q;
+        }
+    }
+    order
+}
+
+// MARK: Baby-step Giant-step
+// Smallest x with g^x = h, where n bounds the order of g, in O(sqrt n) time and memory
+pub fn baby_step_giant_step<O: OpFlag, T: LogElement<O>>(g: T, h: T, n: u64) -> Option<u64> {
+    let mut m = n.isqrt();
+    if m * m < n {
+        m += 1;
+    }
+    let mut baby_steps = HashMap::new();
+    let mut x = T::identity();
+    for j in 0..m {
+        baby_steps.entry(x).or_insert(j);
+        x = Op::<O>::op(&x, g);
+    }
+    // x is g^m here, each giant step multiplies by g^-m
+    let giant_step = Inverse::<O>::inverse(&x);
+    let mut gamma = h;
+    for i in 0..m {
+        if let Some(&j) = baby_steps.get(&gamma) {
+            return Some(i * m + j);
+        }
+        gamma = Op::<O>::op(&gamma, giant_step);
+    }
+    None
+}
+
+// MARK: Pollard Rho
+const RHO_ATTEMPTS: usize = 32;
+
+fn partition<T: Hash>(x: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    x.hash(&mut hasher);
+    hasher.finish() % 3
+}
+
+// Walks x = g^a h^b, squaring or multiplying by g or h depending on which third of the elements x falls in
+fn rho_step<O: OpFlag, T: LogElement<O>>(g: T, h: T, n: u64, (x, a, b): (T, u64, u64)) -> (T, u64, u64) {
+    match partition(&x) {
+        0 => (Op::<O>::op(&x, g), (a + 1) % n, b),
+        1 => (Op::<O>::op(&x, x), mul_mod_u64(a, 2, n), mul_mod_u64(b, 2, n)),
+        _ => (Op::<O>::op(&x, h), a, (b + 1) % n),
+    }
+}
+
+// Some x with g^x = h, where n is the order of g, in O(sqrt n) time and constant memory
+// Works best when n is prime, the walk is seeded from n so results are reproducible
+pub fn pollard_rho_log<O: OpFlag, T: LogElement<O>>(g: T, h: T, n: u64) -> Option<u64> {
+    if n == 1 {
+        return (h == T::identity()).then_some(0);
+    }
+    let mut rng = Rng::new(n);
+    for _ in 0..RHO_ATTEMPTS {
+        let (a, b) = (rng.below(n), rng.below(n));
+        let start = (Op::<O>::op(&pow(g, a as u128), pow(h, b as u128)), a, b);
+        let (mut tortoise, mut hare) = (start, start);
+        loop {
+            tortoise = rho_step(g, h, n, tortoise);
+            hare = rho_step(g, h, n, rho_step(g, h, n, hare));
+            if tortoise.0 == hare.0 {
+                break;
+            }
+        }
+        // g^a h^b = g^A h^B, so x (b - B) = A - a mod n
+        let (_, a, b) = tortoise;
+        let (_, big_a, big_b) = hare;
+        let r = (b + n - big_b) % n;
+        let s = (big_a + n - a) % n;
+        let d = gcd(r as u128, n as u128) as u64;
+        if r == 0 || !s.is_multiple_of(d) || d > 1 << 16 {
+            continue;
+        }
+        let reduced = n / d;
+        let Some(r_inv) = mod_inverse((r / d) as u128, reduced as u128) else { continue };
+        let x0 = mul_mod_u64(s / d % reduced, r_inv as u64, reduced);
+        if let Some(x) = (0..d).map(|k| x0 + k * reduced).find(|&x| pow(g, x as u128) == h) {
+            return Some(x);
+        }
+    }
+    None
+}
+
+// MARK: Pohlig-Hellman
+// Below this prime order the logs of each digit are found with baby-step giant-step, above it with rho
+const BSGS_LIMIT: u64 = 1 << 20;
+
+fn prime_order_log<O: OpFlag, T: LogElement<O>>(g: T, h: T, q: u64) -> Option<u64> {
+    if q < BSGS_LIMIT { baby_step_giant_step(g, h, q) } else { pollard_rho_log(g, h, q) }
+}
+
+// The x mod n with g^x = h, where n is the order of g and factors its factorization
+// Solves one base q digit at a time in each subgroup of order q^e, then glues them with the CRT
+pub fn pohlig_hellman<O: OpFlag, T: LogElement<O>>(g: T, h: T, n: u64, factors: &[(u64, u32)]) -> Option<u64> {
+    let mut residues = Vec::with_capacity(factors.len());
+    for &(q, e) in factors {
+        let qe = q.pow(e);
+        let g_i = pow(g, (n / qe) as u128);
+        let h_i = pow(h, (n / qe) as u128);
+        let gamma = pow(g_i, (qe / q) as u128);
+        let (mut x, mut qk) = (0, 1);
+        for _ in 0..e {
+            let shifted = Op::<O>::op(&Inverse::<O>::inverse(&pow(g_i, x as u128)), h_i);
+            let digit = prime_order_log(gamma, pow(shifted, (qe / (qk * q)) as u128), q)?;
+            x += digit * qk;
+            qk *= q;
+        }
+        residues.push((x as u128, qe as u128));
+    }
+    let (x, _) = crt_pairs(&residues).ok()?;
+    let x = x as u64;
+    (pow(g, x as u128) == h).then_some(x)
+}
+
+// MARK: Groups
+// Smallest x with g^x = h in a finite group, None if h is not a power of g
+pub fn discrete_log<O: OpFlag, T: LogElement<O>>(group: &dyn GroupT<O, T>, g: T, h: T) -> Option<u64> {
+    let Cardinality::Finite(n) = group.order() else { return None };
+    log_with_multiple(g, h, n as u64)
+}
+
+fn log_with_multiple<O: OpFlag, T: LogElement<O>>(g: T, h: T, n: u64) -> Option<u64> {
+    let order = order_from_multiple(g, n, factorize_u64(n).as_slice());
+    pohlig_hellman(g, h, order, factorize_u64(order).as_slice())
+}
+
+// Logs in (Z/M)^x, where every order divides the Carmichael function of M
+pub fn discrete_log_mod<const M: u64>(g: U64CoprimeMod<M>, h: U64CoprimeMod<M>) -> Option<u64> {
+    log_with_multiple::<MUL, _>(g, h, carmichael_lambda_u64(M))
+}
+
+// Logs in the multiplicative group of F_P, None for 0
+pub fn discrete_log_field<const P: u32>(g: U32Field<P>, h: U32Field<P>) -> Option<u64>
+where crate::util::Assert<{is_prime(P)}>: crate::util::IsTrue,
+{
+    if g.0 == 0 || h.0 == 0 {
+        return None;
+    }
+    log_with_multiple::<MUL, _>(g, h, P as u64 - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use crate::set::UniversalSet;
+
+    #[test]
+    fn test_baby_step_giant_step() {
+        let g = U32Field::<1_000_003>(2);
+        let h = pow::<MUL, _>(g, 777_777);
+        let x = baby_step_giant_step::<MUL, _>(g, h, 1_000_002).unwrap();
+        assert_eq!(pow::<MUL, _>(g, x as u128), h);
+        assert_eq!(baby_step_giant_step::<MUL, _>(U32Field::<13>(3), U32Field(2), 12), None);
+    }
+
+    #[test]
+    fn test_pollard_rho() {
+        // 4 generates the subgroup of prime order q in (Z/2q+1)^x
+        const P: u64 = 1_099_511_628_443;
+        const Q: u64 = 549_755_814_221;
+        let g = U64CoprimeMod::<P>::try_new(4).unwrap();
+        let h = U64CoprimeMod::<P>::try_new(358_709_617_362).unwrap();
+        assert_eq!(pollard_rho_log::<MUL, _>(g, h, Q), Some(31_415_926_535));
+    }
+
+    #[test]
+    fn test_pohlig_hellman() {
+        const P: u64 = 576_460_959_300_714_797;
+        let g = U64CoprimeMod::<P>::try_new(2).unwrap();
+        let h = U64CoprimeMod::<P>::try_new(32_471_152_802_243_282).unwrap();
+        assert_eq!(discrete_log_mod(g, h), Some(123_456_789_012_345));
+
+        let g = U64CoprimeMod::<1_000_000_007>::try_new(5).unwrap();
+        assert_eq!(discrete_log_mod(g, pow::<MUL, _>(g, 123_456_789)), Some(123_456_789));
+
+        // Composite modulus, 3 has order 4 mod 10 and 7 = 3^3
+        let g = U64CoprimeMod::<10>::try_new(3).unwrap();
+        assert_eq!(discrete_log_mod(g, U64CoprimeMod::try_new(7).unwrap()), Some(3));
+        let g = U64CoprimeMod::<10>::try_new(9).unwrap();
+        assert_eq!(discrete_log_mod(g, U64CoprimeMod::try_new(3).unwrap()), None);
+    }
+
+    #[test]
+    fn test_field_and_group_logs() {
+        let g = U32Field::<65_537>(3);
+        assert_eq!(discrete_log_field(g, pow::<MUL, _>(g, 40_000)), Some(40_000));
+        assert_eq!(discrete_log_field(g, U32Field(0)), None);
+        // Exponents past u64 reduce mod the order 2^16
+        assert_eq!(pow::<MUL, _>(g, (1 << 100) + 5), U32Field(243));
+
+        // In Z/1000 under addition, 3x = 7 for x = 669
+        let group = Group::<ADD, U32Mod<1000>>::new_unchecked(Rc::new(UniversalSet::new(Cardinality::Finite(1000))));
+        assert_eq!(discrete_log(&group, U32Mod(3), U32Mod(7)), Some(669));
+        assert_eq!(discrete_log(&group, U32Mod(4), U32Mod(7)), None);
+    }
+}
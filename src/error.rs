@@ -0,0 +1,74 @@
+use std::fmt::{self, Debug, Display, Formatter};
+
+// Why an algebraic object could not be constructed, carrying the elements that break the axiom
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error<T = ()> {
+    // a * b lands outside the set
+    NotClosed { a: T, b: T },
+    // (a * b) * c != a * (b * c)
+    NotAssociative { a: T, b: T, c: T },
+    // The identity of the type is not in the set
+    MissingIdentity,
+    // The identity of the type does not act as one on element
+    IdentityFails { element: T },
+    // The inverse of element is missing from the set, or does not multiply to the identity
+    NotInvertible { element: T },
+    // a * x = b or x * a = b has no unique solution x
+    NotDivisible { a: T, b: T },
+    // The check needs to iterate over a set that cannot be iterated
+    NotIterable,
+    MalformedTable { reason: &'static str },
+    // The same element was listed twice where a set was expected
+    DuplicateElement { element: T },
+    // Both operands are valid, but the operation between them is undefined (different tables, different moduli, ...)
+    IncompatibleOperands { a: T, b: T },
+    // Not a bijection of {0..N-1} written in one-line notation
+    InvalidPermutation { perm: Vec<usize> },
+    // Not an injective partial map on {0..N-1}
+    InvalidPartialPermutation { map: Vec<Option<usize>> },
+    // Not a map {0..N-1} -> {0..N-1}
+    InvalidTransformation { map: Vec<usize> },
+    // Cycle notation is 1-indexed, so point has to lie in 1..=n
+    PointOutOfRange { point: usize, n: usize },
+    // point appears twice in a cycle, or in two cycles that should be disjoint
+    RepeatedPoint { point: usize },
+    // value shares a factor with modulus, so it is not a unit
+    NotCoprime { value: u128, modulus: u128 },
+    // Residues need a positive modulus
+    ZeroModulus,
+    // modulus shares a factor with the product of the moduli before it, so the CRT does not apply
+    ModuliNotCoprime { modulus: u128, product: u128 },
+    // The product of the moduli does not fit in a u128
+    ModulusOverflow,
+    // The structure is finite, but its order does not fit in a usize
+    OrderTooLarge,
+}
+
+impl<T: Debug> Display for Error<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotClosed { a, b } => write!(f, "{:?} * {:?} is not in the set", a, b),
+            Error::NotAssociative { a, b, c } => write!(f, "({:?} * {:?}) * {:?} != {:?} * ({:?} * {:?})", a, b, c, a, b, c),
+            Error::MissingIdentity => write!(f, "the identity is not in the set"),
+            Error::IdentityFails { element } => write!(f, "the identity does not fix {:?}", element),
+            Error::NotInvertible { element } => write!(f, "{:?} has no inverse in the set", element),
+            Error::NotDivisible { a, b } => write!(f, "{:?} cannot be divided by {:?}", b, a),
+            Error::NotIterable => write!(f, "the set cannot be iterated"),
+            Error::MalformedTable { reason } => write!(f, "malformed Cayley table: {}", reason),
+            Error::DuplicateElement { element } => write!(f, "{:?} appears more than once", element),
+            Error::IncompatibleOperands { a, b } => write!(f, "{:?} and {:?} cannot be combined", a, b),
+            Error::InvalidPermutation { perm } => write!(f, "{:?} is not a permutation", perm),
+            Error::InvalidPartialPermutation { map } => write!(f, "{:?} is not a partial permutation", map),
+            Error::InvalidTransformation { map } => write!(f, "{:?} is not a transformation", map),
+            Error::PointOutOfRange { point, n } => write!(f, "{} is not a point of 1..={}", point, n),
+            Error::RepeatedPoint { point } => write!(f, "{} appears more than once", point),
+            Error::NotCoprime { value, modulus } => write!(f, "{} is not coprime to {}", value, modulus),
+            Error::ZeroModulus => write!(f, "the modulus must be positive"),
+            Error::ModuliNotCoprime { modulus, product } => write!(f, "{} is not coprime to {}", modulus, product),
+            Error::ModulusOverflow => write!(f, "the product of the moduli overflows"),
+            Error::OrderTooLarge => write!(f, "the order does not fit in a usize"),
+        }
+    }
+}
+
+impl<T: Debug> std::error::Error for Error<T> {}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::Hash;
+use crate::{ops::*, error::Error};
+
+// Green's relations of a finite semigroup, computed once from its multiplication table
+// Classes are stored as lists of indices into elements, in order of first appearance
+pub struct GreensRelations<T> {
+    elements: Vec<T>,
+    table: Vec<Vec<usize>>,
+    r_classes: Vec<Vec<usize>>,
+    l_classes: Vec<Vec<usize>>,
+    h_classes: Vec<Vec<usize>>,
+    j_classes: Vec<Vec<usize>>,
+}
+
+// Groups indices by a key, keeping the classes in order of first appearance
+fn classes_by_key<K: Eq + Hash>(keys: Vec<K>) -> Vec<Vec<usize>> {
+    let mut classes: Vec<Vec<usize>> = Vec::new();
+    let mut index_of_key = HashMap::new();
+    for (x, key) in keys.into_iter().enumerate() {
+        let index = *index_of_key.entry(key).or_insert_with(|| {
+            classes.push(Vec::new());
+            classes.len() - 1
+        });
+        classes[index].push(x);
+    }
+    classes
+}
+
+// Root of x in a union-find forest, halving the path on the way up
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+impl<T> GreensRelations<T> {
+    // Fails with NotClosed when a product of two elements is not among them
+    pub fn new<O: OpFlag>(elements: Vec<T>) -> Result<Self, Error<T>>
+    where T: Op<O> + Hash + Eq + Clone
+    {
+        let n = elements.len();
+        let index: HashMap<&T, usize> = elements.iter().enumerate().map(|(i, x)| (x, i)).collect();
+        let mut table = vec![vec![0; n]; n];
+        for i in 0..n {
+            for j in 0..n {
+                let (a, b) = (&elements[i], &elements[j]);
+                table[i][j] = *index.get(&a.op(b.clone())).ok_or_else(|| Error::NotClosed { a: a.clone(), b: b.clone() })?;
+            }
+        }
+
+        // Principal ideals in S^1, as sorted index lists
+        let ideal = |mut ideal: Vec<usize>, x: usize| {
+            ideal.push(x);
+            ideal.sort();
+            ideal.dedup();
+            ideal
+        };
+        let right_ideals: Vec<Vec<usize>> = (0..n).map(|x| ideal(table[x].clone(), x)).collect();
+        let left_ideals: Vec<Vec<usize>> = (0..n).map(|x| ideal((0..n).map(|s| table[s][x]).collect(), x)).collect();
+
+        let r_classes = classes_by_key(right_ideals.clone());
+        let l_classes = classes_by_key(left_ideals.clone());
+        let h_classes = classes_by_key(right_ideals.into_iter().zip(left_ideals).collect());
+
+        // D = R o L, so the D-classes are the connected components of R and L, and D = J for finite semigroups
+        let mut parent: Vec<usize> = (0..n).collect();
+        for class in r_classes.iter().chain(&l_classes) {
+            for &x in &class[1..] {
+                let (a, b) = (find(&mut parent, class[0]), find(&mut parent, x));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+        let j_classes = classes_by_key((0..n).map(|x| find(&mut parent, x)).collect());
+
+        Ok(GreensRelations { elements, table, r_classes, l_classes, h_classes, j_classes })
+    }
+
+    fn collect_classes(&self, classes: &[Vec<usize>]) -> Vec<Vec<T>>
+    where T: Clone
+    {
+        classes.iter().map(|class| class.iter().map(|&i| self.elements[i].clone()).collect()).collect()
+    }
+
+    fn is_idempotent_index(&self, x: usize) -> bool {
+        self.table[x][x] == x
+    }
+
+    fn is_regular_index(&self, x: usize) -> bool {
+        (0..self.elements.len()).any(|y| self.table[self.table[x][y]][x] == x)
+    }
+
+    pub fn elements(&self) -> &[T] {
+        &self.elements
+    }
+
+    pub fn r_classes(&self) -> Vec<Vec<T>> where T: Clone {
+        self.collect_classes(&self.r_classes)
+    }
+
+    pub fn l_classes(&self) -> Vec<Vec<T>> where T: Clone {
+        self.collect_classes(&self.l_classes)
+    }
+
+    pub fn h_classes(&self) -> Vec<Vec<T>> where T: Clone {
+        self.collect_classes(&self.h_classes)
+    }
+
+    // D = J for finite semigroups
+    pub fn d_classes(&self) -> Vec<Vec<T>> where T: Clone {
+        self.collect_classes(&self.j_classes)
+    }
+
+    pub fn j_classes(&self) -> Vec<Vec<T>> where T: Clone {
+        self.collect_classes(&self.j_classes)
+    }
+
+    pub fn idempotents(&self) -> Vec<T> where T: Clone {
+        (0..self.elements.len()).filter(|&x| self.is_idempotent_index(x)).map(|x| self.elements[x].clone()).collect()
+    }
+
+    // x is regular when x y x = x for some y
+    pub fn regular_elements(&self) -> Vec<T> where T: Clone {
+        (0..self.elements.len()).filter(|&x| self.is_regular_index(x)).map(|x| self.elements[x].clone()).collect()
+    }
+
+    pub fn is_regular(&self) -> bool {
+        (0..self.elements.len()).all(|x| self.is_regular_index(x))
+    }
+
+    // A regular semigroup whose idempotents commute
+    pub fn is_inverse_semigroup(&self) -> bool {
+        let idempotents: Vec<usize> = (0..self.elements.len()).filter(|&x| self.is_idempotent_index(x)).collect();
+        self.is_regular() && idempotents.iter().all(|&e| idempotents.iter().all(|&f| self.table[e][f] == self.table[f][e]))
+    }
+
+    // Every element is idempotent
+    pub fn is_band(&self) -> bool {
+        (0..self.elements.len()).all(|x| self.is_idempotent_index(x))
+    }
+
+    // Egg-box diagram of the d-th D-class: rows are its R-classes, columns its L-classes, cells the H-classes
+    pub fn egg_box(&self, d: usize) -> EggBox<T> where T: Clone {
+        let d_class = &self.j_classes[d];
+        let rows: Vec<&Vec<usize>> = self.r_classes.iter().filter(|r| d_class.contains(&r[0])).collect();
+        let cols: Vec<&Vec<usize>> = self.l_classes.iter().filter(|l| d_class.contains(&l[0])).collect();
+
+        let cells = rows.iter().map(|r| {
+            cols.iter().map(|l| {
+                let h: Vec<usize> = r.iter().filter(|x| l.contains(x)).cloned().collect();
+                let is_group = h.iter().any(|&x| self.is_idempotent_index(x));
+                EggBoxCell { elements: h.iter().map(|&x| self.elements[x].clone()).collect(), is_group }
+            }).collect()
+        }).collect();
+
+        EggBox { cells }
+    }
+}
+
+pub struct EggBoxCell<T> {
+    pub elements: Vec<T>,
+    // H-classes containing an idempotent are groups, traditionally marked with a *
+    pub is_group: bool,
+}
+
+pub struct EggBox<T> {
+    cells: Vec<Vec<EggBoxCell<T>>>,
+}
+
+impl<T> EggBox<T> {
+    pub fn num_rows(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.cells.first().map_or(0, |row| row.len())
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> &EggBoxCell<T> {
+        &self.cells[row][col]
+    }
+}
+
+impl<T: Debug> Display for EggBox<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in &self.cells {
+            let cells: Vec<String> = row.iter()
+                .map(|cell| format!("{}{:?}", if cell.is_group { "*" } else { "" }, cell.elements))
+                .collect();
+            writeln!(f, "| {} |", cells.join(" | "))?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformations::*;
+    use crate::partial_permutations::*;
+
+    #[test]
+    fn test_full_transformation_monoid_classes() {
+        let greens = GreensRelations::new::<OP<1>>(all_transformations::<3>().collect()).unwrap();
+
+        // D-classes of T_3 are the maps of each rank
+        assert_eq!(greens.d_classes().len(), 3);
+        assert!(greens.d_classes().iter().all(|d| d.iter().all(|x| x.rank() == d[0].rank())));
+        assert_eq!(greens.r_classes().len(), 1 + 3 + 1);
+        assert_eq!(greens.l_classes().len(), 1 + 3 + 3);
+        assert_eq!(greens.idempotents().len(), 10);
+        assert!(greens.is_regular());
+        assert!(!greens.is_inverse_semigroup());
+        assert!(!greens.is_band());
+
+        let rank_two = greens.d_classes().iter().position(|d| d[0].rank() == 2).unwrap();
+        let egg_box = greens.egg_box(rank_two);
+        assert_eq!((egg_box.num_rows(), egg_box.num_cols()), (3, 3));
+        assert!((0..3).all(|r| (0..3).all(|c| egg_box.cell(r, c).elements.len() == 2)));
+    }
+
+    #[test]
+    fn test_symmetric_inverse_monoid_is_inverse() {
+        let greens = GreensRelations::new::<OP<1>>(all_partial_permutations::<2>().collect()).unwrap();
+        assert!(greens.is_inverse_semigroup());
+        assert_eq!(greens.idempotents().len(), 4);
+    }
+
+    #[test]
+    fn test_band() {
+        let greens = GreensRelations::new::<OP<1>>(vec![Transformation::<2>::constant(0).unwrap(), Transformation::<2>::constant(1).unwrap()]).unwrap();
+        // x y = y for constant maps, so this is a right zero semigroup
+        assert!(greens.is_band());
+        assert_eq!(greens.r_classes().len(), 1);
+        assert_eq!(greens.l_classes().len(), 2);
+
+        // The swap squares to the identity, which is missing
+        let swap = Transformation::<2>::try_new(vec![1, 0]).unwrap();
+        let result = GreensRelations::new::<OP<1>>(vec![swap.clone()]);
+        assert_eq!(result.err(), Some(Error::NotClosed { a: swap.clone(), b: swap }));
+    }
+}
@@ -6,12 +6,22 @@
 #![feature(adt_const_params)]
 #![feature(lazy_get)]
 
+pub mod error;
 pub mod ops;
 pub mod set;
 pub mod algebraic_objects;
 mod util;
 pub mod mod_ints;
+pub mod number_theory;
+pub mod discrete_log;
+pub mod unit_groups;
 pub mod permutations;
+pub mod partial_permutations;
+pub mod transformations;
+pub mod greens_relations;
+pub mod table_magmas;
+pub mod multiplication_tables;
+pub mod random;
 
 #[cfg(test)]
 mod tests {
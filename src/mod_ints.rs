@@ -1,82 +1,149 @@
 use std::{array, ops::{Add, Div, Mul}};
-use crate::{ops::*, util:: *};
+use std::rc::Rc;
+use crate::{ops::*, error::Error, set::SmallSet, algebraic_objects::*, util::*};
+use crate::number_theory::{factorize_u32, jacobi_u32, jacobi_u64, jacobi_u128, sqrt_mod_prime, sqrt_mod};
 use std::marker::ConstParamTy;
 
+// MARK: Widening Arithmetic
+// Operands are assumed to be reduced already, products are taken in twice the width so any modulus works
+
+pub const fn add_mod_u32(a: u32, b: u32, m: u32) -> u32 {
+    ((a as u64 + b as u64) % m as u64) as u32
+}
+
+pub const fn add_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 + b as u128) % m as u128) as u64
+}
+
+pub const fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    let (sum, overflow) = a.overflowing_add(b);
+    if overflow || sum >= m { sum.wrapping_sub(m) } else { sum }
+}
+
+pub const fn mul_mod_u32(a: u32, b: u32, m: u32) -> u32 {
+    ((a as u64 * b as u64) % m as u64) as u32
+}
+
+pub const fn mul_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+// Full 256-bit product, reduced one 128-bit half at a time
+pub const fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if m <= 1 << 64 {
+        return (a % m) * (b % m) % m;
+    }
+
+    let (hi, lo) = widening_mul_u128(a, b);
+    // Fold in hi * 2^128 + lo by shifting the remainder left one bit at a time
+    let mut rem = hi % m;
+    let mut i = 0;
+    while i < 128 {
+        rem = add_mod_u128(rem, rem, m);
+        if (lo >> (127 - i)) & 1 == 1 {
+            rem = add_mod_u128(rem, 1, m);
+        }
+        i += 1;
+    }
+    rem
+}
+
+// (hi, lo) with a * b = hi * 2^32 + lo
+pub const fn widening_mul_u32(a: u32, b: u32) -> (u32, u32) {
+    let product = a as u64 * b as u64;
+    ((product >> 32) as u32, product as u32)
+}
+
+// (hi, lo) with a * b = hi * 2^64 + lo
+pub const fn widening_mul_u64(a: u64, b: u64) -> (u64, u64) {
+    let product = a as u128 * b as u128;
+    ((product >> 64) as u64, product as u64)
+}
+
+// (hi, lo) with a * b = hi * 2^128 + lo
+pub const fn widening_mul_u128(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (lo_lo & MASK) | (mid << 64);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+
 // MARK: Additive Group
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct U32Mod<const M: u32> (pub u32);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct U64Mod<const M: u64> (pub u64);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct U128Mod<const M: u128> (pub u128);
 
-impl<const M: u32> Add<U32Mod<M>> for U32Mod<M> 
-where Assert<{M <= 1 << 31}>: IsTrue {
+impl<const M: u32> Add<U32Mod<M>> for U32Mod<M> {
     type Output = U32Mod<M>;
 
     fn add(self, other: U32Mod<M>) -> U32Mod<M> {
-        U32Mod((self.0 + other.0) % M)
+        U32Mod(add_mod_u32(self.0, other.0, M))
     }
 }
 
-impl<const M: u64> Add<U64Mod<M>> for U64Mod<M> 
-where Assert<{M <= 1 << 63}>: IsTrue {
+impl<const M: u64> Add<U64Mod<M>> for U64Mod<M> {
     type Output = U64Mod<M>;
 
     fn add(self, other: U64Mod<M>) -> U64Mod<M> {
-        U64Mod((self.0 + other.0) % M)
+        U64Mod(add_mod_u64(self.0, other.0, M))
     }
 }
 
-impl<const M: u128> Add<U128Mod<M>> for U128Mod<M> 
-where Assert<{M <= 1 << 127}>: IsTrue {
+impl<const M: u128> Add<U128Mod<M>> for U128Mod<M> {
     type Output = U128Mod<M>;
 
     fn add(self, other: U128Mod<M>) -> U128Mod<M> {
-        U128Mod((self.0 + other.0) % M)
+        U128Mod(add_mod_u128(self.0, other.0, M))
     }
 }
 
-impl<const M: u32> Inverse<ADD> for U32Mod<M> 
-where Assert<{M <= 1 << 31}>: IsTrue {
+impl<const M: u32> Inverse<ADD> for U32Mod<M> {
     fn inverse(&self) -> U32Mod<M> {
-        U32Mod(M - self.0)
+        U32Mod((M - self.0) % M)
     }
 }
 
-impl<const M: u64> Inverse<ADD> for U64Mod<M> 
-where Assert<{M <= 1 << 63}>: IsTrue {
+impl<const M: u64> Inverse<ADD> for U64Mod<M> {
     fn inverse(&self) -> U64Mod<M> {
-        U64Mod(M - self.0)
+        U64Mod((M - self.0) % M)
     }
 }
 
-impl<const M: u128> Inverse<ADD> for U128Mod<M> 
-where Assert<{M <= 1 << 127}>: IsTrue {
+impl<const M: u128> Inverse<ADD> for U128Mod<M> {
     fn inverse(&self) -> U128Mod<M> {
-        U128Mod(M - self.0)
+        U128Mod((M - self.0) % M)
     }
 }
 
-impl<const M: u32> Identity<ADD> for U32Mod<M> 
-where Assert<{M <= 1 << 31}>: IsTrue {
+impl<const M: u32> Identity<ADD> for U32Mod<M> {
     fn identity() -> U32Mod<M> {
         U32Mod(0)
     }
 }
 
-impl<const M: u64> Identity<ADD> for U64Mod<M> 
-where Assert<{M <= 1 << 63}>: IsTrue {
+impl<const M: u64> Identity<ADD> for U64Mod<M> {
     fn identity() -> U64Mod<M> {
         U64Mod(0)
     }
 }
 
-impl<const M: u128> Identity<ADD> for U128Mod<M> 
-where Assert<{M <= 1 << 127}>: IsTrue {
+impl<const M: u128> Identity<ADD> for U128Mod<M> {
     fn identity() -> U128Mod<M> {
         U128Mod(0)
     }
@@ -84,266 +151,1227 @@ where Assert<{M <= 1 << 127}>: IsTrue {
 
 
 // MARK: Multiplicative Monoid
-impl<const M: u32> Mul<U32Mod<M>> for U32Mod<M> 
-where Assert<{M <= 1<<16}>: IsTrue {
+impl<const M: u32> Mul<U32Mod<M>> for U32Mod<M> {
     type Output = U32Mod<M>;
 
     fn mul(self, other: U32Mod<M>) -> U32Mod<M> {
-        U32Mod((self.0 * other.0) % M)
+        U32Mod(mul_mod_u32(self.0, other.0, M))
     }
 }
 
-impl<const M: u64> Mul<U64Mod<M>> for U64Mod<M> 
-where Assert<{M <= 1<<32}>: IsTrue {
+impl<const M: u64> Mul<U64Mod<M>> for U64Mod<M> {
     type Output = U64Mod<M>;
 
     fn mul(self, other: U64Mod<M>) -> U64Mod<M> {
-        U64Mod((self.0 * other.0) % M)
+        U64Mod(mul_mod_u64(self.0, other.0, M))
     }
 }
 
-impl<const M: u128> Mul<U128Mod<M>> for U128Mod<M> 
-where Assert<{M <= 1<<64}>: IsTrue {
+impl<const M: u128> Mul<U128Mod<M>> for U128Mod<M> {
     type Output = U128Mod<M>;
 
     fn mul(self, other: U128Mod<M>) -> U128Mod<M> {
-        U128Mod((self.0 * other.0) % M)
+        U128Mod(mul_mod_u128(self.0, other.0, M))
     }
 }
 
-impl<const M: u32> Identity<MUL> for U32Mod<M> 
-where Assert<{M <= 1<<16}>: IsTrue {
+impl<const M: u32> Identity<MUL> for U32Mod<M> {
     fn identity() -> U32Mod<M> {
         U32Mod(1)
     }
 }
 
-impl<const M: u64> Identity<MUL> for U64Mod<M> 
-where Assert<{M <= 1<<32}>: IsTrue {
+impl<const M: u64> Identity<MUL> for U64Mod<M> {
     fn identity() -> U64Mod<M> {
         U64Mod(1)
     }
 }
 
-impl<const M: u128> Identity<MUL> for U128Mod<M> 
-where Assert<{M <= 1<<64}>: IsTrue {
+impl<const M: u128> Identity<MUL> for U128Mod<M> {
     fn identity() -> U128Mod<M> {
         U128Mod(1)
     }
 }
 // MARK: Multiplicative Group
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct U32CoprimeMod<const M: u32> (u32);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct U64CoprimeMod<const M: u64> (u64);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct U128CoprimeMod<const M: u128> (u128);
 
+// MARK: Euclid
+pub const fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// (g, s, t) with a*s + b*t = g = gcd(a, b), None unless a, b < 2^127 so they and the coefficients fit in i128
+pub const fn xgcd(a: u128, b: u128) -> Option<(u128, i128, i128)> {
+    if a > i128::MAX as u128 || b > i128::MAX as u128 {
+        return None;
+    }
+    let (mut r0, mut r1) = (a as i128, b as i128);
+    let (mut s0, mut s1) = (1i128, 0i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    Some((r0 as u128, s0, t0))
+}
+
+// Inverse of a modulo m, None when they are not coprime
+// Coefficients are kept reduced mod m instead of signed, so every u128 modulus works
+pub const fn mod_inverse(a: u128, m: u128) -> Option<u128> {
+    let (mut r0, mut r1) = (m, a % m);
+    let (mut t0, mut t1) = (0u128, 1u128 % m);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        let qt = mul_mod_u128(q % m, t1, m);
+        (t0, t1) = (t1, if t0 >= qt { t0 - qt } else { m - (qt - t0) });
+    }
+    if r0 != 1 {
+        return None;
+    }
+    Some(t0)
+}
+
+// MARK: Primality
+pub const fn pow_mod_u64(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut acc = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod_u64(acc, base, m);
+        }
+        base = mul_mod_u64(base, base, m);
+        exp >>= 1;
+    }
+    acc
+}
+
+pub const fn pow_mod_u128(mut base: u128, mut exp: u128, m: u128) -> u128 {
+    let mut acc = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod_u128(acc, base, m);
+        }
+        base = mul_mod_u128(base, base, m);
+        exp >>= 1;
+    }
+    acc
+}
+
+// Bases that make Miller-Rabin deterministic below 2^32, and below 3.18 * 10^23 respectively
+const MR_BASES_U32: [u64; 3] = [2, 7, 61];
+const MR_BASES_U64: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+// Adding 41 makes Miller-Rabin deterministic below this bound (Sorenson and Webster), past it u128 primality falls back to BPSW
+const MR_BASES_U128: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+const MR_LIMIT_U128: u128 = 3_317_044_064_679_887_385_961_981;
+
+const fn is_strong_probable_prime_u64(n: u64, a: u64) -> bool {
+    let a = a % n;
+    if a == 0 {
+        return true;
+    }
+    let s = (n - 1).trailing_zeros();
+    let mut x = pow_mod_u64(a, (n - 1) >> s, n);
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+    let mut i = 1;
+    while i < s {
+        x = mul_mod_u64(x, x, n);
+        if x == n - 1 {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn is_strong_probable_prime_u128(n: u128, a: u128) -> bool {
+    let a = a % n;
+    if a == 0 {
+        return true;
+    }
+    let s = (n - 1).trailing_zeros();
+    let mut x = pow_mod_u128(a, (n - 1) >> s, n);
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+    let mut i = 1;
+    while i < s {
+        x = mul_mod_u128(x, x, n);
+        if x == n - 1 {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn miller_rabin_u64(n: u64, bases: &[u64]) -> bool {
+    if n < 2 {
+        return false;
+    }
+    // Small primes and their multiples, which also covers every base
+    let mut i = 0;
+    while i < MR_BASES_U64.len() {
+        let p = MR_BASES_U64[i];
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+        i += 1;
+    }
+    let mut i = 0;
+    while i < bases.len() {
+        if !is_strong_probable_prime_u64(n, bases[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+pub const fn is_prime(n: u32) -> bool {
+    miller_rabin_u64(n as u64, &MR_BASES_U32)
+}
+
+pub const fn is_prime_u64(n: u64) -> bool {
+    miller_rabin_u64(n, &MR_BASES_U64)
+}
+
+// (x - y) mod n and x / 2 mod n for reduced x, y and odd n
+const fn sub_mod_u128(x: u128, y: u128, n: u128) -> u128 {
+    if x >= y { x - y } else { x + (n - y) }
+}
+
+const fn half_mod_u128(x: u128, n: u128) -> u128 {
+    if x.is_multiple_of(2) { x / 2 } else { x / 2 + n / 2 + 1 }
+}
+
+const fn signed_mod_u128(x: i128, n: u128) -> u128 {
+    let r = x.unsigned_abs() % n;
+    if x >= 0 || r == 0 { r } else { n - r }
+}
+
+// Strong Lucas probable prime test with Selfridge's parameters, for odd n that is not a square
+// D is the first of 5, -7, 9, -11, ... with (D/n) = -1, then P = 1 and Q = (1 - D) / 4
+const fn is_strong_lucas_probable_prime_u128(n: u128) -> bool {
+    let mut d: i128 = 5;
+    loop {
+        match jacobi_u128(signed_mod_u128(d, n), n) {
+            -1 => break,
+            0 if d.unsigned_abs() != n => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    }
+    let (d_mod, q_mod) = (signed_mod_u128(d, n), signed_mod_u128((1 - d) / 4, n));
+
+    // U_k, V_k and Q^k for k the leading bits of the odd part of n + 1, doubling and stepping up one bit at a time
+    let s = (n + 1).trailing_zeros();
+    let k = (n + 1) >> s;
+    let (mut u, mut v, mut qk) = (1, 1, q_mod);
+    let mut bit = 127 - k.leading_zeros();
+    while bit > 0 {
+        bit -= 1;
+        u = mul_mod_u128(u, v, n);
+        v = sub_mod_u128(mul_mod_u128(v, v, n), add_mod_u128(qk, qk, n), n);
+        qk = mul_mod_u128(qk, qk, n);
+        if (k >> bit) & 1 == 1 {
+            (u, v) = (half_mod_u128(add_mod_u128(u, v, n), n), half_mod_u128(add_mod_u128(mul_mod_u128(d_mod, u, n), v, n), n));
+            qk = mul_mod_u128(qk, q_mod, n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+    let mut r = 1;
+    while r < s {
+        v = sub_mod_u128(mul_mod_u128(v, v, n), add_mod_u128(qk, qk, n), n);
+        qk = mul_mod_u128(qk, qk, n);
+        if v == 0 {
+            return true;
+        }
+        r += 1;
+    }
+    false
+}
+
+// Deterministic Miller-Rabin below 3.317 * 10^24, and Baillie-PSW (a strong base 2 test and a strong Lucas test) above it
+// BPSW is only probabilistic there: no composite is known to pass it, but none is proven not to exist past 2^64
+pub const fn is_prime_u128(n: u128) -> bool {
+    if n <= u64::MAX as u128 {
+        return is_prime_u64(n as u64);
+    }
+    let mut i = 0;
+    while i < MR_BASES_U128.len() {
+        if n.is_multiple_of(MR_BASES_U128[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    if n < MR_LIMIT_U128 {
+        let mut i = 0;
+        while i < MR_BASES_U128.len() {
+            if !is_strong_probable_prime_u128(n, MR_BASES_U128[i]) {
+                return false;
+            }
+            i += 1;
+        }
+        return true;
+    }
+    let root = n.isqrt();
+    root * root != n && is_strong_probable_prime_u128(n, 2) && is_strong_lucas_probable_prime_u128(n)
+}
+
 impl<const M: u32> U32CoprimeMod<M> {
-    pub fn new(n: u32) -> Self {
-        todo!("Check if n is coprime to M");
+    pub fn try_new(n: u32) -> Result<Self, Error> {
+        let n = n % M;
+        if gcd(n as u128, M as u128) != 1 {
+            return Err(Error::NotCoprime { value: n as u128, modulus: M as u128 });
+        }
+        Ok(U32CoprimeMod(n))
+    }
+
+    pub fn new_unchecked(n: u32) -> Self {
         U32CoprimeMod(n)
     }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
 }
 
 impl<const M: u64> U64CoprimeMod<M> {
-    pub fn new(n: u64) -> Self {
-        todo!("Check if n is coprime to M");
+    pub fn try_new(n: u64) -> Result<Self, Error> {
+        let n = n % M;
+        if gcd(n as u128, M as u128) != 1 {
+            return Err(Error::NotCoprime { value: n as u128, modulus: M as u128 });
+        }
+        Ok(U64CoprimeMod(n))
+    }
+
+    pub fn new_unchecked(n: u64) -> Self {
         U64CoprimeMod(n)
     }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
 }
 
 impl<const M: u128> U128CoprimeMod<M> {
-    pub fn new(n: u128) -> Self {
-        todo!("Check if n is coprime to M");
+    pub fn try_new(n: u128) -> Result<Self, Error> {
+        let n = n % M;
+        if gcd(n, M) != 1 {
+            return Err(Error::NotCoprime { value: n, modulus: M });
+        }
+        Ok(U128CoprimeMod(n))
+    }
+
+    pub fn new_unchecked(n: u128) -> Self {
         U128CoprimeMod(n)
     }
+
+    pub fn value(&self) -> u128 {
+        self.0
+    }
 }
 
-impl<const M: u32> Mul<U32CoprimeMod<M>> for U32CoprimeMod<M> 
-where Assert<{M <= 1<<16}>: IsTrue {
+impl<const M: u32> Mul<U32CoprimeMod<M>> for U32CoprimeMod<M> {
     type Output = U32CoprimeMod<M>;
 
     fn mul(self, other: U32CoprimeMod<M>) -> U32CoprimeMod<M> {
-        U32CoprimeMod((self.0 * other.0) % M)
+        U32CoprimeMod(mul_mod_u32(self.0, other.0, M))
     }
 }
 
-impl<const M: u64> Mul<U64CoprimeMod<M>> for U64CoprimeMod<M> 
-where Assert<{M <= 1<<32}>: IsTrue {
+impl<const M: u64> Mul<U64CoprimeMod<M>> for U64CoprimeMod<M> {
     type Output = U64CoprimeMod<M>;
 
     fn mul(self, other: U64CoprimeMod<M>) -> U64CoprimeMod<M> {
-        U64CoprimeMod((self.0 * other.0) % M)
+        U64CoprimeMod(mul_mod_u64(self.0, other.0, M))
     }
 }
 
-impl<const M: u128> Mul<U128CoprimeMod<M>> for U128CoprimeMod<M> 
-where Assert<{M <= 1<<64}>: IsTrue {
+impl<const M: u128> Mul<U128CoprimeMod<M>> for U128CoprimeMod<M> {
     type Output = U128CoprimeMod<M>;
 
     fn mul(self, other: U128CoprimeMod<M>) -> U128CoprimeMod<M> {
-        U128CoprimeMod((self.0 * other.0) % M)
+        U128CoprimeMod(mul_mod_u128(self.0, other.0, M))
     }
 }
 
-impl<const M: u32> Identity<MUL> for U32CoprimeMod<M> 
-where Assert<{M <= 1<<16}>: IsTrue {
+impl<const M: u32> Identity<MUL> for U32CoprimeMod<M> {
     fn identity() -> U32CoprimeMod<M> {
         U32CoprimeMod(1)
     }
 }
 
-impl<const M: u64> Identity<MUL> for U64CoprimeMod<M> 
-where Assert<{M <= 1<<32}>: IsTrue {
+impl<const M: u64> Identity<MUL> for U64CoprimeMod<M> {
     fn identity() -> U64CoprimeMod<M> {
         U64CoprimeMod(1)
     }
 }
 
-impl<const M: u128> Identity<MUL> for U128CoprimeMod<M> 
-where Assert<{M <= 1<<64}>: IsTrue {
+impl<const M: u128> Identity<MUL> for U128CoprimeMod<M> {
     fn identity() -> U128CoprimeMod<M> {
         U128CoprimeMod(1)
     }
 }
 
-impl<const M: u32> Inverse<MUL> for U32CoprimeMod<M> 
-where Assert<{M <= 1<<16}>: IsTrue {
+impl<const M: u32> Inverse<MUL> for U32CoprimeMod<M> {
     fn inverse(&self) -> U32CoprimeMod<M> {
-        todo!("Extended Euclidean Algorithm");
+        let inv = mod_inverse(self.0 as u128, M as u128).expect("Coprime residues are always invertible");
+        U32CoprimeMod(inv as u32)
     }
 }
 
-impl<const M: u64> Inverse<MUL> for U64CoprimeMod<M> 
-where Assert<{M <= 1<<32}>: IsTrue {
+impl<const M: u64> Inverse<MUL> for U64CoprimeMod<M> {
     fn inverse(&self) -> U64CoprimeMod<M> {
-        todo!("Extended Euclidean Algorithm");
+        let inv = mod_inverse(self.0 as u128, M as u128).expect("Coprime residues are always invertible");
+        U64CoprimeMod(inv as u64)
     }
 }
 
-impl<const M: u128> Inverse<MUL> for U128CoprimeMod<M> 
-where Assert<{M <= 1<<64}>: IsTrue {
+impl<const M: u128> Inverse<MUL> for U128CoprimeMod<M> {
     fn inverse(&self) -> U128CoprimeMod<M> {
-        todo!("Extended Euclidean Algorithm");
+        let inv = mod_inverse(self.0, M).expect("Coprime residues are always invertible");
+        U128CoprimeMod(inv)
+    }
+}
+
+// MARK: Montgomery
+// Stores a * R mod M for R = 2^BITS, so products reduce with shifts and multiplications instead of a division
+// The reduction needs M to be odd, which is checked at compile time, so U64Montgomery<10> does not type check
+macro_rules! montgomery {
+    ($name:ident, $normal:ident, $t:ty, $widening_mul:ident, $add_mod:ident, $mul_mod:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name<const M: $t> ($t) where Assert<{M % 2 == 1}>: IsTrue;
+
+        impl<const M: $t> $name<M> where Assert<{M % 2 == 1}>: IsTrue {
+            // M^-1 mod R by Newton's iteration, each step doubles the number of correct bits
+            const M_INV: $t = {
+                let mut inv: $t = 1;
+                let mut bits = 1;
+                while bits < <$t>::BITS {
+                    inv = inv.wrapping_mul((2 as $t).wrapping_sub(M.wrapping_mul(inv)));
+                    bits *= 2;
+                }
+                inv
+            };
+
+            // R^2 mod M, used to move into Montgomery form
+            const R2: $t = {
+                let r = (<$t>::MAX % M + 1) % M;
+                $mul_mod(r, r, M)
+            };
+
+            // Montgomery reduction of hi * R + lo < M * R, returns it times R^-1 mod M
+            const fn redc((hi, lo): ($t, $t)) -> $t {
+                let (mm_hi, _) = $widening_mul(lo.wrapping_mul(Self::M_INV), M);
+                if hi >= mm_hi { hi - mm_hi } else { hi.wrapping_sub(mm_hi).wrapping_add(M) }
+            }
+
+            pub const fn new(n: $t) -> Self {
+                $name(Self::redc($widening_mul(n % M, Self::R2)))
+            }
+
+            pub const fn value(&self) -> $t {
+                Self::redc((0, self.0))
+            }
+
+            pub const fn to_normal(&self) -> $normal<M> {
+                $normal(self.value())
+            }
+        }
+
+        impl<const M: $t> From<$normal<M>> for $name<M> where Assert<{M % 2 == 1}>: IsTrue {
+            fn from(n: $normal<M>) -> Self {
+                $name::new(n.0)
+            }
+        }
+
+        impl<const M: $t> From<$name<M>> for $normal<M> where Assert<{M % 2 == 1}>: IsTrue {
+            fn from(n: $name<M>) -> Self {
+                n.to_normal()
+            }
+        }
+
+        impl<const M: $t> Add<$name<M>> for $name<M> where Assert<{M % 2 == 1}>: IsTrue {
+            type Output = $name<M>;
+
+            fn add(self, other: $name<M>) -> $name<M> {
+                $name($add_mod(self.0, other.0, M))
+            }
+        }
+
+        impl<const M: $t> Mul<$name<M>> for $name<M> where Assert<{M % 2 == 1}>: IsTrue {
+            type Output = $name<M>;
+
+            fn mul(self, other: $name<M>) -> $name<M> {
+                $name(Self::redc($widening_mul(self.0, other.0)))
+            }
+        }
+
+        impl<const M: $t> Identity<ADD> for $name<M> where Assert<{M % 2 == 1}>: IsTrue {
+            fn identity() -> $name<M> {
+                $name(0)
+            }
+        }
+
+        impl<const M: $t> Identity<MUL> for $name<M> where Assert<{M % 2 == 1}>: IsTrue {
+            fn identity() -> $name<M> {
+                $name::new(1)
+            }
+        }
+
+        impl<const M: $t> Inverse<ADD> for $name<M> where Assert<{M % 2 == 1}>: IsTrue {
+            fn inverse(&self) -> $name<M> {
+                $name((M - self.0) % M)
+            }
+        }
+    };
+}
+
+montgomery!(U32Montgomery, U32Mod, u32, widening_mul_u32, add_mod_u32, mul_mod_u32);
+montgomery!(U64Montgomery, U64Mod, u64, widening_mul_u64, add_mod_u64, mul_mod_u64);
+montgomery!(U128Montgomery, U128Mod, u128, widening_mul_u128, add_mod_u128, mul_mod_u128);
+
+// MARK: Barrett
+// Normal form, products are reduced by multiplying with a precomputed R^2 / M instead of dividing
+// R^2 / M takes twice the width of M, so there is no u128 version, U128Montgomery covers odd u128 moduli
+macro_rules! barrett {
+    ($name:ident, $normal:ident, $t:ty, $wide:ty, $widening_mul:ident, $add_mod:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name<const M: $t> ($t);
+
+        impl<const M: $t> $name<M> {
+            // floor((R^2 - 1) / M), at most one below floor(R^2 / M), which the final correction absorbs
+            const MU: $wide = <$wide>::MAX / M as $wide;
+
+            const fn reduce(x: $wide) -> $t {
+                let (q, _) = $widening_mul(x, Self::MU);
+                let mut r = x - q * M as $wide;
+                while r >= M as $wide {
+                    r -= M as $wide;
+                }
+                r as $t
+            }
+
+            pub const fn new(n: $t) -> Self {
+                $name(n % M)
+            }
+
+            pub const fn value(&self) -> $t {
+                self.0
+            }
+
+            pub const fn to_normal(&self) -> $normal<M> {
+                $normal(self.0)
+            }
+        }
+
+        impl<const M: $t> From<$normal<M>> for $name<M> {
+            fn from(n: $normal<M>) -> Self {
+                $name::new(n.0)
+            }
+        }
+
+        impl<const M: $t> From<$name<M>> for $normal<M> {
+            fn from(n: $name<M>) -> Self {
+                n.to_normal()
+            }
+        }
+
+        impl<const M: $t> Add<$name<M>> for $name<M> {
+            type Output = $name<M>;
+
+            fn add(self, other: $name<M>) -> $name<M> {
+                $name($add_mod(self.0, other.0, M))
+            }
+        }
+
+        impl<const M: $t> Mul<$name<M>> for $name<M> {
+            type Output = $name<M>;
+
+            fn mul(self, other: $name<M>) -> $name<M> {
+                $name(Self::reduce(self.0 as $wide * other.0 as $wide))
+            }
+        }
+
+        impl<const M: $t> Identity<ADD> for $name<M> {
+            fn identity() -> $name<M> {
+                $name(0)
+            }
+        }
+
+        impl<const M: $t> Identity<MUL> for $name<M> {
+            fn identity() -> $name<M> {
+                $name::new(1)
+            }
+        }
+
+        impl<const M: $t> Inverse<ADD> for $name<M> {
+            fn inverse(&self) -> $name<M> {
+                $name((M - self.0) % M)
+            }
+        }
+    };
+}
+
+barrett!(U32Barrett, U32Mod, u32, u64, widening_mul_u64, add_mod_u32);
+barrett!(U64Barrett, U64Mod, u64, u128, widening_mul_u128, add_mod_u64);
+
+// MARK: Runtime Modulus
+// Modulus 0 marks a formal identity, which is what Identity::identity returns since it has no modulus to look at
+// Formal identities take on the modulus of whatever they are combined with
+#[derive(Debug, Clone, Copy)]
+pub struct DynMod {
+    value: u64,
+    modulus: u64,
+}
+
+impl DynMod {
+    pub fn new(value: u64, modulus: u64) -> Result<Self, Error> {
+        if modulus == 0 {
+            return Err(Error::ZeroModulus);
+        }
+        Ok(DynMod { value: value % modulus, modulus })
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    // None for a formal identity
+    pub fn modulus(&self) -> Option<u64> {
+        (self.modulus != 0).then_some(self.modulus)
+    }
+
+    // The common modulus of both operands, None if they have two different ones
+    fn shared_modulus(&self, other: &DynMod) -> Option<u64> {
+        match (self.modulus, other.modulus) {
+            (0, m) | (m, 0) => Some(m),
+            (a, b) => (a == b).then_some(a),
+        }
+    }
+
+    fn reduce(value: u64, modulus: u64) -> DynMod {
+        if modulus == 0 { DynMod { value, modulus } } else { DynMod { value: value % modulus, modulus } }
+    }
+}
+
+// Not Eq, a formal identity equals its residue under every modulus, so equality is not transitive
+impl PartialEq for DynMod {
+    fn eq(&self, other: &Self) -> bool {
+        match self.shared_modulus(other) {
+            Some(0) => self.value == other.value,
+            Some(m) => self.value % m == other.value % m,
+            None => false,
+        }
+    }
+}
+
+impl Add<DynMod> for DynMod {
+    type Output = DynMod;
+
+    fn add(self, other: DynMod) -> DynMod {
+        TryOp::<ADD>::try_op(&self, other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Mul<DynMod> for DynMod {
+    type Output = DynMod;
+
+    fn mul(self, other: DynMod) -> DynMod {
+        TryOp::<MUL>::try_op(&self, other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl TryOp<ADD> for DynMod {
+    fn try_op(&self, other: DynMod) -> Result<DynMod, Error<DynMod>> {
+        match self.shared_modulus(&other) {
+            Some(0) => Ok(DynMod { value: self.value + other.value, modulus: 0 }),
+            Some(m) => Ok(DynMod::reduce(add_mod_u64(self.value % m, other.value % m, m), m)),
+            None => Err(Error::IncompatibleOperands { a: *self, b: other }),
+        }
+    }
+}
+
+impl TryOp<MUL> for DynMod {
+    fn try_op(&self, other: DynMod) -> Result<DynMod, Error<DynMod>> {
+        match self.shared_modulus(&other) {
+            Some(0) => Ok(DynMod { value: self.value * other.value, modulus: 0 }),
+            Some(m) => Ok(DynMod::reduce(mul_mod_u64(self.value % m, other.value % m, m), m)),
+            None => Err(Error::IncompatibleOperands { a: *self, b: other }),
+        }
+    }
+}
+
+impl Identity<ADD> for DynMod {
+    fn identity() -> DynMod {
+        DynMod { value: 0, modulus: 0 }
+    }
+}
+
+impl Identity<MUL> for DynMod {
+    fn identity() -> DynMod {
+        DynMod { value: 1, modulus: 0 }
+    }
+}
+
+impl Inverse<ADD> for DynMod {
+    fn inverse(&self) -> DynMod {
+        match self.modulus {
+            0 => *self,
+            m => DynMod { value: (m - self.value) % m, modulus: m },
+        }
+    }
+}
+
+// Unit modulo a runtime modulus, the runtime counterpart of U64CoprimeMod
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DynCoprimeMod (DynMod);
+
+impl DynCoprimeMod {
+    pub fn try_new(value: u64, modulus: u64) -> Result<Self, Error> {
+        let n = DynMod::new(value, modulus)?;
+        if gcd(n.value as u128, modulus as u128) != 1 {
+            return Err(Error::NotCoprime { value: n.value as u128, modulus: modulus as u128 });
+        }
+        Ok(DynCoprimeMod(n))
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0.value
+    }
+
+    pub fn modulus(&self) -> Option<u64> {
+        self.0.modulus()
+    }
+
+    pub fn as_dyn_mod(&self) -> DynMod {
+        self.0
+    }
+}
+
+impl Mul<DynCoprimeMod> for DynCoprimeMod {
+    type Output = DynCoprimeMod;
+
+    fn mul(self, other: DynCoprimeMod) -> DynCoprimeMod {
+        DynCoprimeMod(self.0 * other.0)
+    }
+}
+
+impl TryOp<MUL> for DynCoprimeMod {
+    fn try_op(&self, other: DynCoprimeMod) -> Result<DynCoprimeMod, Error<DynCoprimeMod>> {
+        match TryOp::<MUL>::try_op(&self.0, other.0) {
+            Ok(n) => Ok(DynCoprimeMod(n)),
+            Err(_) => Err(Error::IncompatibleOperands { a: *self, b: other }),
+        }
+    }
+}
+
+impl Identity<MUL> for DynCoprimeMod {
+    fn identity() -> DynCoprimeMod {
+        DynCoprimeMod(Identity::<MUL>::identity())
+    }
+}
+
+impl Inverse<MUL> for DynCoprimeMod {
+    fn inverse(&self) -> DynCoprimeMod {
+        match self.0.modulus {
+            0 => *self,
+            m => {
+                let inv = mod_inverse(self.0.value as u128, m as u128).expect("Coprime residues are always invertible");
+                DynCoprimeMod(DynMod { value: inv as u64, modulus: m })
+            }
+        }
+    }
+}
+
+// (Z/modulus)^x as a group, with its elements listed out
+pub fn dyn_unit_group(modulus: u64) -> Group<MUL, DynCoprimeMod> {
+    let units: Vec<DynCoprimeMod> = (0..modulus).filter_map(|n| DynCoprimeMod::try_new(n, modulus).ok()).collect();
+    Group::new_unchecked(Rc::new(SmallSet::new(units)))
+}
+
+// MARK: Chinese Remainder
+// Moduli the typed CRT accepts, coprime and with a product that still fits in a u64
+pub const fn crt_compatible(m1: u64, m2: u64) -> bool {
+    gcd(m1 as u128, m2 as u128) == 1 && m1.checked_mul(m2).is_some()
+}
+
+// The unique x mod M1*M2 with x = a mod M1 and x = b mod M2
+pub fn crt<const M1: u64, const M2: u64>(a: U64Mod<M1>, b: U64Mod<M2>) -> U64Mod<{M1 * M2}>
+where Assert<{crt_compatible(M1, M2)}>: IsTrue,
+{
+    let (residue, _) = crt_pairs(&[(a.0 as u128, M1 as u128), (b.0 as u128, M2 as u128)])
+        .expect("Moduli are checked to be coprime");
+    U64Mod(residue as u64)
+}
+
+// Residues of x mod M1 and mod M2, undoing crt
+pub fn crt_decompose<const M1: u64, const M2: u64>(x: U64Mod<{M1 * M2}>) -> (U64Mod<M1>, U64Mod<M2>)
+where Assert<{crt_compatible(M1, M2)}>: IsTrue,
+{
+    (U64Mod(x.0 % M1), U64Mod(x.0 % M2))
+}
+
+// Combines (residue, modulus) pairs into a single (residue, product of moduli)
+// Fails on a zero modulus, on a modulus sharing a factor with the product so far, and when the product outgrows u128
+pub fn crt_pairs(pairs: &[(u128, u128)]) -> Result<(u128, u128), Error> {
+    let mut acc = (0u128, 1u128);
+    for &(residue, modulus) in pairs {
+        if modulus == 0 {
+            return Err(Error::ZeroModulus);
+        }
+        let (x, m) = acc;
+        let Some(m_inv) = mod_inverse(m % modulus, modulus) else {
+            return Err(Error::ModuliNotCoprime { modulus, product: m });
+        };
+        let product = m.checked_mul(modulus).ok_or(Error::ModulusOverflow)?;
+        // x + m * ((residue - x) / m mod modulus)
+        let diff = add_mod_u128(residue % modulus, modulus - x % modulus, modulus);
+        let k = mul_mod_u128(diff, m_inv, modulus);
+        acc = (x + m * k, product);
+    }
+    Ok(acc)
+}
+
+// Residues of x modulo each of the moduli, undoing crt_pairs
+pub fn crt_residues(x: u128, moduli: &[u128]) -> Vec<(u128, u128)> {
+    moduli.iter().map(|&m| (x % m, m)).collect()
+}
+
+// Direct product of two rings with componentwise operations, Z/mn is isomorphic to ModProduct<Z/m, Z/n> for coprime m, n
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModProduct<A, B> (pub A, pub B);
+
+impl<A: Add<Output=A>, B: Add<Output=B>> Add<ModProduct<A, B>> for ModProduct<A, B> {
+    type Output = ModProduct<A, B>;
+
+    fn add(self, other: ModProduct<A, B>) -> ModProduct<A, B> {
+        ModProduct(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+impl<A: Mul<Output=A>, B: Mul<Output=B>> Mul<ModProduct<A, B>> for ModProduct<A, B> {
+    type Output = ModProduct<A, B>;
+
+    fn mul(self, other: ModProduct<A, B>) -> ModProduct<A, B> {
+        ModProduct(self.0 * other.0, self.1 * other.1)
+    }
+}
+
+impl<A: Identity<ADD> + Copy, B: Identity<ADD> + Copy> Identity<ADD> for ModProduct<A, B> where Self: Op<ADD> {
+    fn identity() -> ModProduct<A, B> {
+        ModProduct(A::identity(), B::identity())
+    }
+}
+
+impl<A: Identity<MUL> + Copy, B: Identity<MUL> + Copy> Identity<MUL> for ModProduct<A, B> where Self: Op<MUL> {
+    fn identity() -> ModProduct<A, B> {
+        ModProduct(A::identity(), B::identity())
+    }
+}
+
+impl<A: Inverse<ADD> + Copy, B: Inverse<ADD> + Copy> Inverse<ADD> for ModProduct<A, B> where Self: Identity<ADD> {
+    fn inverse(&self) -> ModProduct<A, B> {
+        ModProduct(self.0.inverse(), self.1.inverse())
+    }
+}
+
+// The ring isomorphism Z/mn -> Z/m x Z/n, and back
+impl<const M1: u64, const M2: u64> From<U64Mod<{M1 * M2}>> for ModProduct<U64Mod<M1>, U64Mod<M2>>
+where Assert<{crt_compatible(M1, M2)}>: IsTrue,
+{
+    fn from(x: U64Mod<{M1 * M2}>) -> Self {
+        let (a, b) = crt_decompose::<M1, M2>(x);
+        ModProduct(a, b)
+    }
+}
+
+impl<const M1: u64, const M2: u64> From<ModProduct<U64Mod<M1>, U64Mod<M2>>> for U64Mod<{M1 * M2}>
+where Assert<{crt_compatible(M1, M2)}>: IsTrue,
+{
+    fn from(x: ModProduct<U64Mod<M1>, U64Mod<M2>>) -> Self {
+        crt(x.0, x.1)
     }
 }
 
 // MARK: PreComped ModInts
-trait PrecompedInverse<const M: u32> 
-where [u32; M as usize]: {
-    fn precomped_inverse(&self) -> Self;
+// inverse_table::<M>()[x] is the inverse of x mod M, or 0 when x is not a unit
+pub const fn inverse_table<const M: u32>() -> [u32; M as usize] {
+    let mut table = [0; M as usize];
+    let mut x = 0;
+    while x < M {
+        if let Some(inv) = mod_inverse(x as u128, M as u128) {
+            table[x as usize] = inv as u32;
+        }
+        x += 1;
+    }
+    table
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct U32CoprimeModPrecomp<const M: u32> (u32);
+// Unit mod M with its inverses looked up in a table built at compile time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct U32CoprimeModPrecomp<const M: u32> (u32);
 
-impl<const M: u32> Mul<U32CoprimeModPrecomp<M>> for U32CoprimeModPrecomp<M> 
-where Assert<{M <= 1<<16}>: IsTrue {
+impl<const M: u32> U32CoprimeModPrecomp<M> where [u32; M as usize]: {
+    pub const INVERSES: [u32; M as usize] = inverse_table::<M>();
+
+    pub fn try_new(n: u32) -> Result<Self, Error> {
+        let n = n % M;
+        if gcd(n as u128, M as u128) != 1 {
+            return Err(Error::NotCoprime { value: n as u128, modulus: M as u128 });
+        }
+        Ok(U32CoprimeModPrecomp(n))
+    }
+
+    // n has to be a unit below M already, the inverse table is indexed by it as is
+    pub fn new_unchecked(n: u32) -> Self {
+        U32CoprimeModPrecomp(n)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+impl<const M: u32> Mul<U32CoprimeModPrecomp<M>> for U32CoprimeModPrecomp<M> {
     type Output = U32CoprimeModPrecomp<M>;
 
     fn mul(self, other: U32CoprimeModPrecomp<M>) -> U32CoprimeModPrecomp<M> {
-        U32CoprimeModPrecomp((self.0 * other.0) % M)
+        U32CoprimeModPrecomp(mul_mod_u32(self.0, other.0, M))
     }
 }
 
-impl<const M: u32> Identity<MUL> for U32CoprimeModPrecomp<M> 
-where Assert<{M <= 1<<16}>: IsTrue {
+impl<const M: u32> Identity<MUL> for U32CoprimeModPrecomp<M> {
     fn identity() -> U32CoprimeModPrecomp<M> {
-        U32CoprimeModPrecomp(1)
+        U32CoprimeModPrecomp(1 % M)
     }
 }
 
-impl<const M: u32> Inverse<MUL> for U32CoprimeModPrecomp<M> 
-where Assert<{M <= 1<<16}>: IsTrue,
-      [u32; M as usize]: ,
-      Self: PrecompedInverse<M> 
-{
+impl<const M: u32> Inverse<MUL> for U32CoprimeModPrecomp<M> where [u32; M as usize]: {
     fn inverse(&self) -> U32CoprimeModPrecomp<M> {
-        self.precomped_inverse()
+        U32CoprimeModPrecomp(Self::INVERSES[self.0 as usize])
     }
 }
 
 // MARK: Field
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-// TODO: Fix all this
-// Assert P is prime
-pub struct U32Field<const P: u32> (pub u32) where Assert<{P <= 1 << 31}>: IsTrue;
+// P is checked to be prime at compile time, so U32Field<12> does not type check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct U32Field<const P: u32> (pub u32) where Assert<{is_prime(P)}>: IsTrue;
 
-impl <const P: u32> Add<U32Field<P>> for U32Field<P> 
-where Assert<{P <= 1 << 31}>: IsTrue
-{
+impl <const P: u32> Add<U32Field<P>> for U32Field<P> where Assert<{is_prime(P)}>: IsTrue {
     type Output = U32Field<P>;
 
     fn add(self, other: U32Field<P>) -> U32Field<P> {
-        U32Field((self.0 + other.0) % P)
+        U32Field(add_mod_u32(self.0, other.0, P))
     }
 }
 
-impl <const P: u32> Mul<U32Field<P>> for U32Field<P> 
-where Assert<{P <= 1 << 16}>: IsTrue,
-      Assert<{P <= 1 << 31}>: IsTrue
-{
+impl <const P: u32> Mul<U32Field<P>> for U32Field<P> where Assert<{is_prime(P)}>: IsTrue {
     type Output = U32Field<P>;
 
     fn mul(self, other: U32Field<P>) -> U32Field<P> {
-        U32Field((self.0 * other.0) % P)
+        U32Field(mul_mod_u32(self.0, other.0, P))
     }
 }
 
-impl <const P: u32> Identity<ADD> for U32Field<P> 
-where Assert<{P <= 1 << 31}>: IsTrue
-{
+impl <const P: u32> Identity<ADD> for U32Field<P> where Assert<{is_prime(P)}>: IsTrue {
     fn identity() -> U32Field<P> {
         U32Field(0)
     }
 }
 
-impl <const P: u32> Identity<MUL> for U32Field<P> 
-where Assert<{P <= 1 << 16}>: IsTrue,
-      Assert<{P <= 1 << 31}>: IsTrue
-{
+impl <const P: u32> Identity<MUL> for U32Field<P> where Assert<{is_prime(P)}>: IsTrue {
     fn identity() -> U32Field<P> {
         U32Field(1)
     }
 }
 
-impl <const P: u32> Inverse<ADD> for U32Field<P> 
-where Assert<{P <= 1 << 31}>: IsTrue
-{
+impl <const P: u32> Inverse<ADD> for U32Field<P> where Assert<{is_prime(P)}>: IsTrue {
     fn inverse(&self) -> U32Field<P> {
-        U32Field(P - self.0)
+        U32Field((P - self.0) % P)
     }
 }
 
-impl <const P: u32> Inverse<MUL> for U32Field<P> 
-where Assert<{P <= 1 << 16}>: IsTrue,
-      Assert<{P <= 1 << 31}>: IsTrue
-{
+impl <const P: u32> Inverse<MUL> for U32Field<P> where Assert<{is_prime(P)}>: IsTrue {
     fn inverse(&self) -> U32Field<P> {
-        todo!("Extended Euclidean Algorithm");
+        let inv = mod_inverse(self.0 as u128, P as u128).expect("0 has no multiplicative inverse");
+        U32Field(inv as u32)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct U64Field<const P: u64> (pub u64) where Assert<{is_prime_u64(P)}>: IsTrue;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl <const P: u64> Add<U64Field<P>> for U64Field<P> where Assert<{is_prime_u64(P)}>: IsTrue {
+    type Output = U64Field<P>;
+
+    fn add(self, other: U64Field<P>) -> U64Field<P> {
+        U64Field(add_mod_u64(self.0, other.0, P))
+    }
+}
+
+impl <const P: u64> Mul<U64Field<P>> for U64Field<P> where Assert<{is_prime_u64(P)}>: IsTrue {
+    type Output = U64Field<P>;
+
+    fn mul(self, other: U64Field<P>) -> U64Field<P> {
+        U64Field(mul_mod_u64(self.0, other.0, P))
+    }
+}
+
+impl <const P: u64> Identity<ADD> for U64Field<P> where Assert<{is_prime_u64(P)}>: IsTrue {
+    fn identity() -> U64Field<P> {
+        U64Field(0)
+    }
+}
+
+impl <const P: u64> Identity<MUL> for U64Field<P> where Assert<{is_prime_u64(P)}>: IsTrue {
+    fn identity() -> U64Field<P> {
+        U64Field(1)
+    }
+}
+
+impl <const P: u64> Inverse<ADD> for U64Field<P> where Assert<{is_prime_u64(P)}>: IsTrue {
+    fn inverse(&self) -> U64Field<P> {
+        U64Field((P - self.0) % P)
+    }
+}
+
+impl <const P: u64> Inverse<MUL> for U64Field<P> where Assert<{is_prime_u64(P)}>: IsTrue {
+    fn inverse(&self) -> U64Field<P> {
+        let inv = mod_inverse(self.0 as u128, P as u128).expect("0 has no multiplicative inverse");
+        U64Field(inv as u64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct U128Field<const P: u128> (pub u128) where Assert<{is_prime_u128(P)}>: IsTrue;
+
+impl <const P: u128> Add<U128Field<P>> for U128Field<P> where Assert<{is_prime_u128(P)}>: IsTrue {
+    type Output = U128Field<P>;
+
+    fn add(self, other: U128Field<P>) -> U128Field<P> {
+        U128Field(add_mod_u128(self.0, other.0, P))
+    }
+}
+
+impl <const P: u128> Mul<U128Field<P>> for U128Field<P> where Assert<{is_prime_u128(P)}>: IsTrue {
+    type Output = U128Field<P>;
+
+    fn mul(self, other: U128Field<P>) -> U128Field<P> {
+        U128Field(mul_mod_u128(self.0, other.0, P))
+    }
+}
+
+impl <const P: u128> Identity<ADD> for U128Field<P> where Assert<{is_prime_u128(P)}>: IsTrue {
+    fn identity() -> U128Field<P> {
+        U128Field(0)
+    }
+}
+
+impl <const P: u128> Identity<MUL> for U128Field<P> where Assert<{is_prime_u128(P)}>: IsTrue {
+    fn identity() -> U128Field<P> {
+        U128Field(1)
+    }
+}
+
+impl <const P: u128> Inverse<ADD> for U128Field<P> where Assert<{is_prime_u128(P)}>: IsTrue {
+    fn inverse(&self) -> U128Field<P> {
+        U128Field((P - self.0) % P)
+    }
+}
+
+impl <const P: u128> Inverse<MUL> for U128Field<P> where Assert<{is_prime_u128(P)}>: IsTrue {
+    fn inverse(&self) -> U128Field<P> {
+        let inv = mod_inverse(self.0, P).expect("0 has no multiplicative inverse");
+        U128Field(inv)
+    }
+}
+
+
+// MARK: Log Tables
+// Smallest generator of (Z/p)^x for a prime p, by checking g^((p-1)/q) != 1 for each prime q dividing p - 1
+const fn smallest_generator_u32(p: u32) -> u32 {
+    let factors = factorize_u32(p - 1);
+    let factors = factors.as_slice();
+    let mut g = 1;
+    loop {
+        let mut i = 0;
+        while i < factors.len() && pow_mod_u64(g as u64, ((p - 1) / factors[i].0) as u64, p as u64) != 1 {
+            i += 1;
+        }
+        if i == factors.len() {
+            return g;
+        }
+        g += 1;
+    }
+}
+
+// Powers g^0, ..., g^(p-2) of the smallest generator, entry p-1 is unused
+pub const fn antilog_table<const P: u32>() -> [u32; P as usize] {
+    let g = smallest_generator_u32(P);
+    let mut table = [0; P as usize];
+    let (mut i, mut x) = (0, 1 % P);
+    while i + 1 < P {
+        table[i as usize] = x;
+        x = mul_mod_u32(x, g, P);
+        i += 1;
+    }
+    table
+}
+
+// Discrete logs to the smallest generator, entry 0 is unused
+pub const fn log_table<const P: u32>() -> [u32; P as usize] {
+    let antilog = antilog_table::<P>();
+    let mut table = [0; P as usize];
+    let mut i = 0;
+    while i + 1 < P {
+        table[antilog[i as usize] as usize] = i;
+        i += 1;
+    }
+    table
+}
 
-    const INVERSES_MOD_7: [u32; 6] = [1, 4, 5, 2, 3, 6];
+// Largest prime a log field is built for, each of its two tables takes 4 * P bytes
+pub const LOG_FIELD_LIMIT: u32 = 1 << 16;
 
-    impl PrecompedInverse<7> for U32CoprimeModPrecomp<7> {
-        fn precomped_inverse(&self) -> Self {
+// Field element whose products and inverses are table lookups, for primes up to LOG_FIELD_LIMIT
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct U32LogField<const P: u32> (pub u32) where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue;
 
-            Self(INVERSES_MOD_7[(self.0 - 1) as usize])
+impl <const P: u32> U32LogField<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue, [u32; P as usize]: {
+    pub const LOG: [u32; P as usize] = log_table::<P>();
+    pub const ANTILOG: [u32; P as usize] = antilog_table::<P>();
+}
+
+impl <const P: u32> From<U32Field<P>> for U32LogField<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue {
+    fn from(x: U32Field<P>) -> Self {
+        U32LogField(x.0)
+    }
+}
+
+impl <const P: u32> From<U32LogField<P>> for U32Field<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue {
+    fn from(x: U32LogField<P>) -> Self {
+        U32Field(x.0)
+    }
+}
+
+impl <const P: u32> Add<U32LogField<P>> for U32LogField<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue {
+    type Output = U32LogField<P>;
+
+    fn add(self, other: U32LogField<P>) -> U32LogField<P> {
+        U32LogField(add_mod_u32(self.0, other.0, P))
+    }
+}
+
+impl <const P: u32> Mul<U32LogField<P>> for U32LogField<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue, [u32; P as usize]: {
+    type Output = U32LogField<P>;
+
+    fn mul(self, other: U32LogField<P>) -> U32LogField<P> {
+        if self.0 == 0 || other.0 == 0 {
+            return U32LogField(0);
         }
+        let log = (Self::LOG[self.0 as usize] as u64 + Self::LOG[other.0 as usize] as u64) % (P as u64 - 1);
+        U32LogField(Self::ANTILOG[log as usize])
+    }
+}
+
+impl <const P: u32> Identity<ADD> for U32LogField<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue {
+    fn identity() -> U32LogField<P> {
+        U32LogField(0)
+    }
+}
+
+impl <const P: u32> Identity<MUL> for U32LogField<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue, [u32; P as usize]: {
+    fn identity() -> U32LogField<P> {
+        U32LogField(1)
+    }
+}
+
+impl <const P: u32> Inverse<ADD> for U32LogField<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue {
+    fn inverse(&self) -> U32LogField<P> {
+        U32LogField((P - self.0) % P)
+    }
+}
+
+impl <const P: u32> Inverse<MUL> for U32LogField<P> where Assert<{is_prime(P)}>: IsTrue, Assert<{P <= LOG_FIELD_LIMIT}>: IsTrue, [u32; P as usize]: {
+    fn inverse(&self) -> U32LogField<P> {
+        assert!(self.0 != 0, "0 has no multiplicative inverse");
+        let log = (P - 1 - Self::LOG[self.0 as usize]) % (P - 1);
+        U32LogField(Self::ANTILOG[log as usize])
+    }
+}
+// MARK: Square Roots
+// Residues count a as a square when some x has x^2 = a, so 0 is one
+impl <const P: u32> U32Field<P> where Assert<{is_prime(P)}>: IsTrue {
+    pub fn is_quadratic_residue(&self) -> bool {
+        P == 2 || jacobi_u32(self.0, P) != -1
+    }
+
+    // One square root, the other is its additive inverse
+    pub fn sqrt(&self) -> Option<Self> {
+        sqrt_mod_prime(self.0 as u64, P as u64).map(|r| U32Field(r as u32))
+    }
+
+    pub fn sqrts(&self) -> Vec<Self> {
+        let mut roots: Vec<u64> = sqrt_mod(self.0 as u64, P as u64).collect();
+        roots.sort_unstable();
+        roots.into_iter().map(|r| U32Field(r as u32)).collect()
+    }
+}
+
+impl <const P: u64> U64Field<P> where Assert<{is_prime_u64(P)}>: IsTrue {
+    pub fn is_quadratic_residue(&self) -> bool {
+        P == 2 || jacobi_u64(self.0, P) != -1
     }
 
+    // One square root, the other is its additive inverse
+    pub fn sqrt(&self) -> Option<Self> {
+        sqrt_mod_prime(self.0, P).map(U64Field)
+    }
+
+    pub fn sqrts(&self) -> Vec<Self> {
+        let mut roots: Vec<u64> = sqrt_mod(self.0, P).collect();
+        roots.sort_unstable();
+        roots.into_iter().map(U64Field).collect()
+    }
+}
+
+impl<const M: u64> U64Mod<M> {
+    // Every x with x^2 = self in no particular order, lazily since there can be many when M has repeated prime factors
+    pub fn sqrts(&self) -> impl Iterator<Item = Self> {
+        sqrt_mod(self.0, M).map(U64Mod)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVERSES_MOD_7: [u32; 7] = [0, 1, 4, 5, 2, 3, 6];
+
     fn g(x: u32) -> U32CoprimeModPrecomp<7> {
-        U32CoprimeModPrecomp(x)
+        U32CoprimeModPrecomp::try_new(x).unwrap()
     }
 
     #[test]
@@ -355,7 +1383,267 @@ mod tests {
         assert_eq!(c, g(1));
         assert_eq!(a.inverse(), b);
         assert_eq!(b.inverse(), a);
-        
+        assert_eq!(U32CoprimeModPrecomp::<7>::INVERSES, INVERSES_MOD_7);
+
+        for x in 1..360 {
+            if let Ok(x) = U32CoprimeModPrecomp::<360>::try_new(x) {
+                assert_eq!(x * x.inverse(), U32CoprimeModPrecomp::identity());
+            }
+        }
+    }
+
+    #[test]
+    fn test_log_tables() {
+        assert_eq!(antilog_table::<7>(), [1, 3, 2, 6, 4, 5, 0]);
+        assert_eq!(log_table::<7>()[1..], [0, 2, 1, 4, 5, 3]);
+
+        for a in 0..251 {
+            for b in 0..251 {
+                let product: U32Field<251> = (U32LogField::<251>(a) * U32LogField(b)).into();
+                assert_eq!(product, U32Field(a) * U32Field(b));
+            }
+            if a != 0 {
+                let x = U32LogField::<251>(a);
+                assert_eq!(x * Inverse::<MUL>::inverse(&x), U32LogField(1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_range_arithmetic() {
+        const P: u32 = 998_244_353;
+        let x = U32Mod::<P>(P - 1);
+        assert_eq!(x * x, U32Mod(1));
+        assert_eq!(x + x, U32Mod(P - 2));
+
+        const MERSENNE: u64 = (1 << 61) - 1;
+        let y = U64Mod::<MERSENNE>(MERSENNE - 2);
+        assert_eq!(y * y, U64Mod(4));
+
+        const BIG: u128 = u128::MAX - 158; // largest prime below 2^128
+        let z = U128Mod::<BIG>(BIG - 1);
+        assert_eq!(z * z, U128Mod(1));
+        assert_eq!(z + z, U128Mod(BIG - 2));
+        assert_eq!(U128Mod::<BIG>(1 << 100) * U128Mod(1 << 100), U128Mod(mul_mod_u128(1 << 72, 159, BIG)));
+        assert_eq!(Inverse::<ADD>::inverse(&U32Mod::<7>(0)), U32Mod(0));
+    }
+
+    fn check_backends<const M: u64>(values: &[u64]) where Assert<{M % 2 == 1}>: IsTrue {
+        for &a in values {
+            for &b in values {
+                let expected = U64Mod::<M>(a % M) * U64Mod(b % M);
+                assert_eq!((U64Montgomery::<M>::new(a) * U64Montgomery::new(b)).to_normal(), expected);
+                assert_eq!(U64Mod::from(U64Barrett::<M>::new(a) * U64Barrett::new(b)), expected);
+                assert_eq!(U64Montgomery::<M>::new(a) + U64Montgomery::new(b), U64Montgomery::new((U64Mod::<M>(a % M) + U64Mod(b % M)).0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_montgomery_and_barrett() {
+        let values = [0, 1, 2, 12345, 998_244_352, (1 << 61) - 2, u64::MAX - 1, u64::MAX];
+        check_backends::<998_244_353>(&values);
+        check_backends::<{ (1 << 61) - 1 }>(&values);
+        check_backends::<{ u64::MAX }>(&values);
+        check_backends::<1>(&values);
+
+        let n = std::num::NonZero::new(1_000_000).unwrap();
+        let x = U64Montgomery::<1_000_000_007>::new(3);
+        assert_eq!(Associative::<MUL>::repeated_op(&x, n).to_normal(), Associative::<MUL>::repeated_op(&U64Mod::<1_000_000_007>(3), n));
+        assert_eq!(<U64Barrett<10> as Identity<MUL>>::identity(), U64Barrett::new(11));
+        assert_eq!(U64Montgomery::<9>::from(U64Mod(4)).value(), 4);
+    }
+
+    #[test]
+    fn test_u32_and_u128_backends() {
+        const P: u32 = 4_294_967_291; // largest prime below 2^32
+        let values = [0, 1, 2, 65_537, P - 1, u32::MAX];
+        for &a in &values {
+            for &b in &values {
+                let expected = U32Mod::<P>(a % P) * U32Mod(b % P);
+                assert_eq!(U32Montgomery::<P>::new(a) * U32Montgomery::new(b), U32Montgomery::from(expected));
+                assert_eq!(U32Barrett::<P>::new(a) * U32Barrett::new(b), U32Barrett::from(expected));
+                assert_eq!(U32Barrett::<10>::new(a) * U32Barrett::new(b), U32Barrett::from(U32Mod::<10>(a % 10) * U32Mod(b % 10)));
+            }
+        }
+
+        const BIG: u128 = u128::MAX - 158; // largest prime below 2^128
+        let values = [0, 1, 2, 1 << 100, BIG - 1, u128::MAX];
+        for &a in &values {
+            for &b in &values {
+                let expected = U128Mod::<BIG>(a % BIG) * U128Mod(b % BIG);
+                assert_eq!((U128Montgomery::<BIG>::new(a) * U128Montgomery::new(b)).to_normal(), expected);
+                assert_eq!((U128Montgomery::<{ (1 << 61) - 1 }>::new(a) * U128Montgomery::new(b)).value(), mul_mod_u128(a, b, (1 << 61) - 1));
+            }
+        }
+        assert_eq!(<U128Montgomery<BIG> as Identity<MUL>>::identity().value(), 1);
+    }
+
+    #[test]
+    fn test_runtime_modulus() {
+        let a = DynMod::new(10, 7).unwrap();
+        let b = DynMod::new(5, 7).unwrap();
+        assert_eq!(a + b, DynMod::new(1, 7).unwrap());
+        assert_eq!(a * b, DynMod::new(1, 7).unwrap());
+        assert_eq!(a + Identity::<ADD>::identity(), a);
+        assert_eq!(a * Identity::<MUL>::identity(), a);
+        assert_eq!(a + Inverse::<ADD>::inverse(&a), DynMod::new(0, 7).unwrap());
+        assert_eq!(DynMod::new(0, 7).unwrap(), Identity::<ADD>::identity());
+
+        let c = DynMod::new(3, 8).unwrap();
+        assert_eq!(TryOp::<ADD>::try_op(&a, c), Err(Error::IncompatibleOperands { a, b: c }));
+        assert!(TryOp::<MUL>::try_op(&a, b).is_ok());
+        assert_ne!(a, DynMod::new(3, 11).unwrap());
+        assert_eq!(DynMod::new(3, 0), Err(Error::ZeroModulus));
+    }
+
+    #[test]
+    fn test_runtime_units() {
+        let x = DynCoprimeMod::try_new(7, 20).unwrap();
+        assert_eq!(x * x.inverse(), Identity::<MUL>::identity());
+        assert_eq!(DynCoprimeMod::try_new(6, 20), Err(Error::NotCoprime { value: 6, modulus: 20 }));
+        assert_eq!(DynCoprimeMod::try_new(1, 0), Err(Error::ZeroModulus));
+
+        let units = dyn_unit_group(20);
+        assert_eq!(units.order(), crate::set::Cardinality::Finite(8));
+        assert_eq!(units.order_of_element(x), Some(4));
+        assert!(Group::<MUL, DynCoprimeMod>::try_new(units.get_set()).is_ok());
+    }
+
+    #[test]
+    fn test_primality() {
+        let small: Vec<u32> = (0..50).filter(|&n| is_prime(n)).collect();
+        assert_eq!(small, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]);
+        assert!(is_prime(u32::MAX - 4));
+        assert!(!is_prime(3_215_031_751));
+        assert!(is_prime_u64(18_446_744_073_709_551_557));
+        assert!(!is_prime_u64(3_825_123_056_546_413_051));
+        assert!(is_prime_u128((1 << 127) - 1));
+        assert!(!is_prime_u128(((1 << 61) - 1) * ((1 << 31) - 1)));
+        assert!(is_prime_u128((1 << 89) - 1));
+        assert!(is_prime_u128(u128::MAX - 158));
+        assert!(!is_prime_u128(((1 << 61) - 1) * ((1 << 61) - 1)));
+        // 2^64 + 13 is the smallest prime past u64, decided by the deterministic bases
+        assert!(is_prime_u128((1 << 64) + 13));
+        assert!(!is_prime_u128((1 << 64) + 11));
+        // Strong pseudoprimes to every prime base up to 37, caught by 41, and up to 41, caught by the Lucas test
+        assert!(!is_prime_u128(318_665_857_834_031_151_167_461));
+        assert!(!is_prime_u128(3_317_044_064_679_887_385_961_981));
+
+        // 5459 and 5777 are the smallest strong Lucas pseudoprimes, but the pair of tests agrees with the u64 answer
+        assert!(is_strong_lucas_probable_prime_u128(5459) && is_strong_lucas_probable_prime_u128(5777));
+        for n in (3..20_000u128).step_by(2).filter(|&n| n.isqrt().pow(2) != n) {
+            let bpsw = is_strong_probable_prime_u128(n, 2) && is_strong_lucas_probable_prime_u128(n);
+            assert_eq!(bpsw, is_prime_u64(n as u64), "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_crt() {
+        let x = crt(U64Mod::<4>(3), U64Mod::<9>(5));
+        assert_eq!(x, U64Mod::<36>(23));
+        assert_eq!(crt_decompose::<4, 9>(x), (U64Mod(3), U64Mod(5)));
+
+        assert_eq!(crt_pairs(&[(2, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+        assert_eq!(crt_pairs(&[]), Ok((0, 1)));
+        assert_eq!(crt_pairs(&[(1, 4), (3, 6)]), Err(Error::ModuliNotCoprime { modulus: 6, product: 4 }));
+        assert_eq!(crt_pairs(&[(1, 4), (0, 0)]), Err(Error::ZeroModulus));
+        assert_eq!(crt_pairs(&[(1, 1 << 100), (2, (1 << 31) - 1)]), Err(Error::ModulusOverflow));
+        assert_eq!(crt_residues(23, &[3, 5, 7]), vec![(2, 3), (3, 5), (2, 7)]);
+
+        let big = [(1, (1 << 61) - 1), (2, (1 << 31) - 1), (3, 1 << 20)];
+        let (x, m) = crt_pairs(&big).unwrap();
+        assert_eq!(crt_residues(x, &[(1 << 61) - 1, (1 << 31) - 1, 1 << 20]), big.to_vec());
+        assert_eq!(m, ((1 << 61) - 1) * ((1 << 31) - 1) * (1 << 20));
+    }
+
+    #[test]
+    fn test_crt_isomorphism() {
+        type Pair = ModProduct<U64Mod<4>, U64Mod<9>>;
+        let phi = |x: u64| Pair::from(U64Mod::<36>(x));
+        for x in 0..36 {
+            assert_eq!(U64Mod::<36>::from(phi(x)), U64Mod(x));
+            for y in 0..36 {
+                assert_eq!(phi(x) + phi(y), Pair::from(U64Mod::<36>(x) + U64Mod(y)));
+                assert_eq!(phi(x) * phi(y), Pair::from(U64Mod::<36>(x) * U64Mod(y)));
+            }
+        }
+        assert_eq!(phi(1), Identity::<MUL>::identity());
+        assert_eq!(phi(0), Identity::<ADD>::identity());
+        assert_eq!(phi(35), Inverse::<ADD>::inverse(&phi(1)));
+    }
+
+    #[test]
+    fn test_square_roots() {
+        let x = U32Field::<1_000_003>(2);
+        assert_eq!(x.is_quadratic_residue(), x.sqrt().is_some());
+        let y = U32Field::<1_000_003>(123_456) * U32Field(123_456);
+        let root = y.sqrt().unwrap();
+        assert_eq!(root * root, y);
+        assert_eq!(y.sqrts(), vec![U32Field(123_456), U32Field(1_000_003 - 123_456)]);
+        assert_eq!(U32Field::<13>(0).sqrts(), vec![U32Field(0)]);
+        assert!(!U32Field::<13>(2).is_quadratic_residue());
+
+        const P: u64 = 576_460_959_300_714_797;
+        let z = U64Field::<P>(3) * U64Field(3);
+        assert_eq!(z.sqrts(), vec![U64Field(3), U64Field(P - 3)]);
+
+        let mut roots: Vec<u64> = U64Mod::<105>(4).sqrts().map(|r| r.0).collect();
+        roots.sort_unstable();
+        assert_eq!(roots, vec![2, 23, 37, 47, 58, 68, 82, 103]);
+        let mut roots: Vec<u64> = U64Mod::<27>(9).sqrts().map(|r| r.0).collect();
+        roots.sort_unstable();
+        assert_eq!(roots, vec![3, 6, 12, 15, 21, 24]);
+        assert!(U64Mod::<{1 << 62}>(0).sqrts().take(5).all(|r| r * r == U64Mod(0)));
+    }
+
+    #[test]
+    fn test_euclid() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        let (g, s, t) = xgcd(240, 46).unwrap();
+        assert_eq!(g, 2);
+        assert_eq!(240 * s + 46 * t, 2);
+        let (a, b) = (i128::MAX as u128, (1 << 100) + 7);
+        let (g, s, t) = xgcd(a, b).unwrap();
+        assert_eq!(g, gcd(a, b));
+        // a s alone can overflow, but the wrapped sum is exact since g fits
+        assert_eq!((a as i128).wrapping_mul(s).wrapping_add((b as i128).wrapping_mul(t)), g as i128);
+        assert_eq!(xgcd(1 << 127, 3), None);
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(4, 8), None);
+        assert_eq!(mod_inverse(2, (1 << 126) + 1), Some((1 << 125) + 1));
+        assert_eq!(mod_inverse(2, u128::MAX), Some(1 << 127));
+        assert_eq!(mod_inverse(0, 1), Some(0));
+    }
+
+    #[test]
+    fn test_multiplicative_inverses() {
+        for n in 1..12 {
+            if let Ok(x) = U32CoprimeMod::<12>::try_new(n) {
+                assert_eq!(x * x.inverse(), U32CoprimeMod::identity());
+            }
+        }
+
+        let x = U64CoprimeMod::<1_000_003>::try_new(123_456).unwrap();
+        assert_eq!(x * x.inverse(), U64CoprimeMod::identity());
+        let x = U128CoprimeMod::<{ (1 << 61) - 1 }>::try_new(987_654_321).unwrap();
+        assert_eq!((x.inverse().value() * 987_654_321) % ((1 << 61) - 1), 1);
+
+        let x = U32Field::<65_521>(40_000);
+        assert_eq!(x * Inverse::<MUL>::inverse(&x), U32Field(1));
+        let x = U64Field::<1_000_000_007>(123_456_789);
+        assert_eq!(x * Inverse::<MUL>::inverse(&x), U64Field(1));
+        let x = U128Field::<{ (1 << 89) - 1 }>(1 << 70);
+        assert_eq!(x * Inverse::<MUL>::inverse(&x), U128Field(1));
+    }
+
+    #[test]
+    fn test_coprime_constructor() {
+        assert!(U32CoprimeMod::<12>::try_new(5).is_ok());
+        assert_eq!(U32CoprimeMod::<12>::try_new(17), Ok(U32CoprimeMod::new_unchecked(5)));
+        assert_eq!(U64CoprimeMod::<12>::try_new(9), Err(Error::NotCoprime { value: 9, modulus: 12 }));
+        assert!(U128CoprimeMod::<1>::try_new(0).is_ok());
     }
 
 }
\ No newline at end of file
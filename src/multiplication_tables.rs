@@ -0,0 +1,205 @@
+use std::fmt::Display;
+use crate::ops::*;
+use crate::algebraic_objects::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Plain,
+    Markdown,
+    Latex,
+    Csv,
+}
+
+// Cayley table of a finite magma, with the identity (if any) in the first row and column
+pub struct MultiplicationTable<T> {
+    elements: Vec<T>,
+    labels: Vec<String>,
+    products: Vec<Vec<usize>>,
+}
+
+impl<T> MultiplicationTable<T> {
+    // None when the set of the magma cannot be iterated
+    pub fn from_magma<O: OpFlag>(magma: &dyn MagmaT<O, T>) -> Option<Self>
+    where T: Op<O> + PartialEq + Clone + Display
+    {
+        Self::from_magma_with_labels(magma, |x| x.to_string())
+    }
+
+    pub fn from_magma_with_labels<O: OpFlag>(magma: &dyn MagmaT<O, T>, label: impl Fn(&T) -> String) -> Option<Self>
+    where T: Op<O> + PartialEq + Clone
+    {
+        let mut elements: Vec<T> = magma.get_set().iter()?.collect();
+        let n = elements.len();
+
+        let product = |a: &T, b: &T| a.op(b.clone());
+        let identity = (0..n).find(|&e| elements.iter().all(|x| product(&elements[e], x) == *x && product(x, &elements[e]) == *x));
+        if let Some(e) = identity {
+            let e = elements.remove(e);
+            elements.insert(0, e);
+        }
+
+        let index_of = |x: &T| elements.iter().position(|y| y == x).expect("Magma is not closed under the operation");
+        let products = (0..n)
+            .map(|a| (0..n).map(|b| index_of(&product(&elements[a], &elements[b]))).collect())
+            .collect();
+        let labels = elements.iter().map(label).collect();
+
+        Some(MultiplicationTable { elements, labels, products })
+    }
+
+    pub fn elements(&self) -> &[T] {
+        &self.elements
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    // Row a column b holds the index of a * b, in the order of elements
+    pub fn products(&self) -> &[Vec<usize>] {
+        &self.products
+    }
+
+    pub fn render(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Plain => self.render_plain(),
+            TableFormat::Markdown => self.render_markdown(),
+            TableFormat::Latex => self.render_latex(),
+            TableFormat::Csv => self.render_csv(),
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (&String, Vec<&String>)> {
+        self.labels.iter().zip(&self.products).map(|(label, row)| (label, row.iter().map(|&x| &self.labels[x]).collect()))
+    }
+
+    fn render_plain(&self) -> String {
+        let width = self.labels.iter().map(|l| l.chars().count()).max().unwrap_or(0).max(1);
+        let pad = |s: &str| format!("{:>width$}", s, width = width);
+
+        let mut out = format!("{} |", pad("*"));
+        for label in &self.labels {
+            out += &format!(" {}", pad(label));
+        }
+        out += &format!("\n{}-+{}\n", "-".repeat(width), "-".repeat((width + 1) * self.labels.len()));
+        for (label, row) in self.rows() {
+            out += &format!("{} |", pad(label));
+            for entry in row {
+                out += &format!(" {}", pad(entry));
+            }
+            out += "\n";
+        }
+        out
+    }
+
+    fn render_markdown(&self) -> String {
+        // A bare | would end the cell, and * _ ` would start emphasis or code
+        let escape = |s: &str| {
+            s.chars().fold(String::new(), |mut out, c| {
+                if matches!(c, '\\' | '|' | '*' | '_' | '`') {
+                    out.push('\\');
+                }
+                out.push(c);
+                out
+            })
+        };
+
+        let header: Vec<String> = self.labels.iter().map(|l| escape(l)).collect();
+        let mut out = format!("| * | {} |\n", header.join(" | "));
+        out += &format!("|---|{}\n", "---|".repeat(self.labels.len()));
+        for (label, row) in self.rows() {
+            let row: Vec<String> = row.iter().map(|s| escape(s)).collect();
+            out += &format!("| **{}** | {} |\n", escape(label), row.join(" | "));
+        }
+        out
+    }
+
+    fn render_latex(&self) -> String {
+        // Labels are set as text, so every special character of LaTeX has to be escaped
+        let escape = |s: &str| {
+            s.chars().fold(String::new(), |mut out, c| {
+                match c {
+                    '\\' => out += "\\textbackslash{}",
+                    '~' => out += "\\textasciitilde{}",
+                    '^' => out += "\\textasciicircum{}",
+                    '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                        out.push('\\');
+                        out.push(c);
+                    }
+                    _ => out.push(c),
+                }
+                out
+            })
+        };
+
+        let header: Vec<String> = self.labels.iter().map(|l| escape(l)).collect();
+        let mut out = format!("\\begin{{tabular}}{{c|{}}}\n", "c".repeat(self.labels.len()));
+        out += &format!("$\\cdot$ & {} \\\\\n\\hline\n", header.join(" & "));
+        for (label, row) in self.rows() {
+            let row: Vec<String> = row.iter().map(|s| escape(s)).collect();
+            out += &format!("{} & {} \\\\\n", escape(label), row.join(" & "));
+        }
+        out += "\\end{tabular}\n";
+        out
+    }
+
+    fn render_csv(&self) -> String {
+        let escape = |s: &str| {
+            if s.contains([',', '"', '\n']) {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            } else {
+                s.to_string()
+            }
+        };
+
+        let header: Vec<String> = self.labels.iter().map(|l| escape(l)).collect();
+        let mut out = format!(",{}\n", header.join(","));
+        for (label, row) in self.rows() {
+            let row: Vec<String> = row.iter().map(|s| escape(s)).collect();
+            out += &format!("{},{}\n", escape(label), row.join(","));
+        }
+        out
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_magmas::*;
+
+    #[test]
+    fn test_identity_first() {
+        // 1 is the identity of this cyclic group of order 3, stored out of order
+        let group = table_group(vec![vec![2, 0, 1], vec![0, 1, 2], vec![1, 2, 0]]).unwrap();
+        let table = MultiplicationTable::from_magma(&group).unwrap();
+        assert_eq!(table.labels(), &["1", "0", "2"]);
+        assert_eq!(table.products()[0], vec![0, 1, 2]);
+
+        assert_eq!(table.render(TableFormat::Plain), "* | 1 0 2\n--+------\n1 | 1 0 2\n0 | 0 2 1\n2 | 2 1 0\n");
+        assert_eq!(table.render(TableFormat::Csv), ",1,0,2\n1,1,0,2\n0,0,2,1\n2,2,1,0\n");
+    }
+
+    #[test]
+    fn test_named_labels() {
+        let group = table_group(vec![vec![0, 1], vec![1, 0]]).unwrap();
+        let names = ["e", "a, b"];
+        let table = MultiplicationTable::from_magma_with_labels(&group, |x| names[x.index().unwrap()].to_string()).unwrap();
+
+        assert_eq!(table.render(TableFormat::Markdown), "| * | e | a, b |\n|---|---|---|\n| **e** | e | a, b |\n| **a, b** | a, b | e |\n");
+        assert_eq!(table.render(TableFormat::Csv), ",e,\"a, b\"\ne,e,\"a, b\"\n\"a, b\",\"a, b\",e\n");
+        assert!(table.render(TableFormat::Latex).starts_with("\\begin{tabular}{c|cc}\n$\\cdot$ & e & a, b \\\\\n\\hline\n"));
+    }
+
+    #[test]
+    fn test_escaped_labels() {
+        let group = table_group(vec![vec![0, 1], vec![1, 0]]).unwrap();
+        let names = ["e", "a|b_1"];
+        let table = MultiplicationTable::from_magma_with_labels(&group, |x| names[x.index().unwrap()].to_string()).unwrap();
+
+        assert_eq!(table.render(TableFormat::Markdown), "| * | e | a\\|b\\_1 |\n|---|---|---|\n| **e** | e | a\\|b\\_1 |\n| **a\\|b\\_1** | a\\|b\\_1 | e |\n");
+        let names = ["1", "50% & $x_1$ \\ {y}"];
+        let table = MultiplicationTable::from_magma_with_labels(&group, |x| names[x.index().unwrap()].to_string()).unwrap();
+        assert!(table.render(TableFormat::Latex).contains("& 50\\% \\& \\$x\\_1\\$ \\textbackslash{} \\{y\\} \\\\\n"));
+    }
+}
@@ -0,0 +1,592 @@
+use crate::mod_ints::{add_mod_u64, add_mod_u128, mul_mod_u64, mul_mod_u128, pow_mod_u64};
+pub use crate::mod_ints::{gcd, xgcd, mod_inverse, is_prime, is_prime_u64, is_prime_u128};
+
+// MARK: Factorization
+// Distinct prime factors with exponents, in increasing order
+// Kept in a fixed-size buffer so factoring can run in const fns, N is the most distinct primes a T can have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Factorization<T, const N: usize> {
+    factors: [(T, u32); N],
+    len: usize,
+}
+
+impl<T, const N: usize> Factorization<T, N> {
+    pub const fn as_slice(&self) -> &[(T, u32)] {
+        self.factors.split_at(self.len).0
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T: Copy, const N: usize> Factorization<T, N> {
+    pub fn to_vec(&self) -> Vec<(T, u32)> {
+        self.as_slice().to_vec()
+    }
+}
+
+// 2 * 3 * ... * 29 is the first primorial past u32::MAX, 2 * ... * 53 past u64::MAX and 2 * ... * 107 past u128::MAX
+pub type FactorizationU32 = Factorization<u32, 9>;
+pub type FactorizationU64 = Factorization<u64, 15>;
+pub type FactorizationU128 = Factorization<u128, 27>;
+
+const TRIAL_DIVISION_LIMIT: u64 = 1 << 10;
+
+// Const fns cannot be generic over the integer type, so each width gets its own copy of these
+macro_rules! factoring_helpers {
+    ($insert:ident, $rho:ident, $t:ty, $factorization:ty, $add_mod:ident, $mul_mod:ident) => {
+        const fn $insert(f: &mut $factorization, p: $t, e: u32) {
+            let mut i = 0;
+            while i < f.len && f.factors[i].0 < p {
+                i += 1;
+            }
+            if i < f.len && f.factors[i].0 == p {
+                f.factors[i].1 += e;
+                return;
+            }
+            let mut j = f.len;
+            while j > i {
+                f.factors[j] = f.factors[j - 1];
+                j -= 1;
+            }
+            f.factors[i] = (p, e);
+            f.len += 1;
+        }
+
+        // A nontrivial factor of a composite n with no small prime factors, Floyd cycle finding on x^2 + c
+        const fn $rho(n: $t) -> $t {
+            let mut c = 1;
+            loop {
+                let (mut x, mut y, mut d) = (2, 2, 1);
+                while d == 1 {
+                    x = $add_mod($mul_mod(x, x, n), c, n);
+                    y = $add_mod($mul_mod(y, y, n), c, n);
+                    y = $add_mod($mul_mod(y, y, n), c, n);
+                    d = gcd(x.abs_diff(y) as u128, n as u128) as $t;
+                }
+                if d != n {
+                    return d;
+                }
+                c += 1;
+            }
+        }
+    };
+}
+
+factoring_helpers!(insert_u64, pollard_rho_u64, u64, FactorizationU64, add_mod_u64, mul_mod_u64);
+factoring_helpers!(insert_u128, pollard_rho_u128, u128, FactorizationU128, add_mod_u128, mul_mod_u128);
+
+// Trial division by small numbers, then Pollard rho on what is left until Miller-Rabin says each piece is prime
+pub const fn factorize_u64(mut n: u64) -> FactorizationU64 {
+    assert!(n > 0, "0 has no prime factorization");
+    let mut f = Factorization { factors: [(0, 0); 15], len: 0 };
+    let mut p = 2;
+    while p < TRIAL_DIVISION_LIMIT && p * p <= n {
+        let mut e = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            e += 1;
+        }
+        if e > 0 {
+            insert_u64(&mut f, p, e);
+        }
+        p += 1;
+    }
+    // Each split adds one piece, and there are at most 64 prime factors counted with multiplicity
+    let mut stack = [0u64; 64];
+    let mut top = 0;
+    if n > 1 {
+        stack[0] = n;
+        top = 1;
+    }
+    while top > 0 {
+        top -= 1;
+        let m = stack[top];
+        if is_prime_u64(m) {
+            insert_u64(&mut f, m, 1);
+        } else {
+            let d = pollard_rho_u64(m);
+            stack[top] = d;
+            stack[top + 1] = m / d;
+            top += 2;
+        }
+    }
+    f
+}
+
+pub const fn factorize_u32(n: u32) -> FactorizationU32 {
+    let wide = factorize_u64(n as u64);
+    let mut f = Factorization { factors: [(0, 0); 9], len: wide.len };
+    let mut i = 0;
+    while i < wide.len {
+        f.factors[i] = (wide.factors[i].0 as u32, wide.factors[i].1);
+        i += 1;
+    }
+    f
+}
+
+pub const fn factorize_u128(mut n: u128) -> FactorizationU128 {
+    assert!(n > 0, "0 has no prime factorization");
+    let mut f = Factorization { factors: [(0, 0); 27], len: 0 };
+    let mut p = 2;
+    while p < TRIAL_DIVISION_LIMIT as u128 && p * p <= n {
+        let mut e = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            e += 1;
+        }
+        if e > 0 {
+            insert_u128(&mut f, p, e);
+        }
+        p += 1;
+    }
+    let mut stack = [0u128; 128];
+    let mut top = 0;
+    if n > 1 {
+        stack[0] = n;
+        top = 1;
+    }
+    while top > 0 {
+        top -= 1;
+        let m = stack[top];
+        if m <= u64::MAX as u128 {
+            // Pieces that fit in a u64 are finished off with the faster u64 arithmetic
+            let narrow = factorize_u64(m as u64);
+            let mut i = 0;
+            while i < narrow.len {
+                insert_u128(&mut f, narrow.factors[i].0 as u128, narrow.factors[i].1);
+                i += 1;
+            }
+        } else if is_prime_u128(m) {
+            insert_u128(&mut f, m, 1);
+        } else {
+            let d = pollard_rho_u128(m);
+            stack[top] = d;
+            stack[top + 1] = m / d;
+            top += 2;
+        }
+    }
+    f
+}
+
+// MARK: Arithmetic Functions
+// Size of (Z/n)^x
+pub const fn euler_phi_u64(n: u64) -> u64 {
+    let f = factorize_u64(n);
+    let (mut phi, mut i) = (1, 0);
+    while i < f.len {
+        let (p, e) = f.factors[i];
+        phi *= (p - 1) * p.pow(e - 1);
+        i += 1;
+    }
+    phi
+}
+
+pub const fn euler_phi_u32(n: u32) -> u32 {
+    euler_phi_u64(n as u64) as u32
+}
+
+pub const fn euler_phi_u128(n: u128) -> u128 {
+    let f = factorize_u128(n);
+    let (mut phi, mut i) = (1, 0);
+    while i < f.len {
+        let (p, e) = f.factors[i];
+        phi *= (p - 1) * p.pow(e - 1);
+        i += 1;
+    }
+    phi
+}
+
+// Exponent of (Z/p^e)^x, which is cyclic except for powers of 2 past 4
+const fn prime_power_lambda(p: u128, e: u32) -> u128 {
+    if p == 2 && e >= 3 { 1 << (e - 2) } else { (p - 1) * p.pow(e - 1) }
+}
+
+const fn lcm(a: u128, b: u128) -> u128 {
+    a / gcd(a, b) * b
+}
+
+// Exponent of (Z/n)^x, the smallest m with x^m = 1 for every unit x
+pub const fn carmichael_lambda_u128(n: u128) -> u128 {
+    let f = factorize_u128(n);
+    let (mut lambda, mut i) = (1, 0);
+    while i < f.len {
+        let (p, e) = f.factors[i];
+        lambda = lcm(lambda, prime_power_lambda(p, e));
+        i += 1;
+    }
+    lambda
+}
+
+pub const fn carmichael_lambda_u64(n: u64) -> u64 {
+    let f = factorize_u64(n);
+    let (mut lambda, mut i) = (1, 0);
+    while i < f.len {
+        let (p, e) = f.factors[i];
+        lambda = lcm(lambda, prime_power_lambda(p as u128, e));
+        i += 1;
+    }
+    lambda as u64
+}
+
+pub const fn carmichael_lambda_u32(n: u32) -> u32 {
+    carmichael_lambda_u64(n as u64) as u32
+}
+
+// 0 when n has a square factor, otherwise -1 to the number of prime factors
+pub const fn mobius_u64(n: u64) -> i8 {
+    let f = factorize_u64(n);
+    let mut i = 0;
+    while i < f.len {
+        if f.factors[i].1 > 1 {
+            return 0;
+        }
+        i += 1;
+    }
+    if f.len.is_multiple_of(2) { 1 } else { -1 }
+}
+
+pub const fn mobius_u32(n: u32) -> i8 {
+    mobius_u64(n as u64)
+}
+
+pub const fn mobius_u128(n: u128) -> i8 {
+    let f = factorize_u128(n);
+    let mut i = 0;
+    while i < f.len {
+        if f.factors[i].1 > 1 {
+            return 0;
+        }
+        i += 1;
+    }
+    if f.len.is_multiple_of(2) { 1 } else { -1 }
+}
+
+// Jacobi symbol (a/n) for odd n, by quadratic reciprocity so n is never factored
+pub const fn jacobi_u128(a: u128, n: u128) -> i8 {
+    assert!(n % 2 == 1, "The Jacobi symbol needs an odd modulus");
+    let (mut a, mut n) = (a % n, n);
+    let mut result = 1;
+    while a != 0 {
+        while a % 2 == 0 {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        (a, n) = (n, a);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    if n == 1 { result } else { 0 }
+}
+
+pub const fn jacobi_u64(a: u64, n: u64) -> i8 {
+    jacobi_u128(a as u128, n as u128)
+}
+
+pub const fn jacobi_u32(a: u32, n: u32) -> i8 {
+    jacobi_u128(a as u128, n as u128)
+}
+
+// MARK: Divisors
+// Every divisor of n in increasing order
+pub fn divisors_u128(n: u128) -> Vec<u128> {
+    let mut divisors = vec![1];
+    for &(p, e) in factorize_u128(n).as_slice() {
+        let smaller = divisors.clone();
+        let mut pk = 1;
+        for _ in 0..e {
+            pk *= p;
+            divisors.extend(smaller.iter().map(|d| d * pk));
+        }
+    }
+    divisors.sort_unstable();
+    divisors
+}
+
+pub fn divisors_u64(n: u64) -> Vec<u64> {
+    divisors_u128(n as u128).into_iter().map(|d| d as u64).collect()
+}
+
+pub fn divisors_u32(n: u32) -> Vec<u32> {
+    divisors_u128(n as u128).into_iter().map(|d| d as u32).collect()
+}
+
+// MARK: Square Roots
+// A square root of a mod an odd prime p by Tonelli-Shanks, None for non-residues, the other root is p - r
+pub fn sqrt_mod_prime(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if p == 2 || a == 0 {
+        return Some(a);
+    }
+    if jacobi_u64(a, p) != 1 {
+        return None;
+    }
+    // p - 1 = q 2^s with q odd, z is any non-residue
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p).find(|&z| jacobi_u64(z, p) == -1).expect("Odd primes have non-residues");
+    let (mut m, mut c) = (s, pow_mod_u64(z, q, p));
+    let (mut t, mut r) = (pow_mod_u64(a, q, p), pow_mod_u64(a, q.div_ceil(2), p));
+    while t != 1 {
+        // Least i with t^(2^i) = 1
+        let (mut i, mut t2) = (0, t);
+        while t2 != 1 {
+            t2 = mul_mod_u64(t2, t2, p);
+            i += 1;
+        }
+        let b = pow_mod_u64(c, 1 << (m - i - 1), p);
+        (m, c) = (i, mul_mod_u64(b, b, p));
+        (t, r) = (mul_mod_u64(t, c, p), mul_mod_u64(r, b, p));
+    }
+    Some(r)
+}
+
+// Square roots of a unit u mod p^k, at most four of them
+fn unit_sqrts_mod_prime_power(u: u64, p: u64, k: u32) -> Vec<u64> {
+    let pk = p.pow(k);
+    if p == 2 {
+        return match k {
+            1 => vec![1],
+            2 => if u % 4 == 1 { vec![1, 3] } else { vec![] },
+            _ => {
+                if u % 8 != 1 {
+                    return vec![];
+                }
+                // Fix one more bit of the root for each power of 2
+                let mut r: u64 = 1;
+                for i in 3..k {
+                    if !(r as u128 * r as u128).wrapping_sub(u as u128).is_multiple_of(1 << (i + 1)) {
+                        r += 1 << (i - 1);
+                    }
+                }
+                let half = pk / 2;
+                vec![r, pk - r, (r + half) % pk, (pk - r + half) % pk]
+            }
+        };
+    }
+    let Some(mut r) = sqrt_mod_prime(u % p, p) else { return vec![] };
+    // Hensel lifting, r <- r - (r^2 - u) / 2r one power of p at a time
+    let mut q = p;
+    for _ in 1..k {
+        q *= p;
+        let f = add_mod_u64(mul_mod_u64(r, r, q), q - u % q, q);
+        let inv = mod_inverse(add_mod_u64(r, r, q) as u128, q as u128).expect("2r is a unit for odd p") as u64;
+        r = add_mod_u64(r, q - mul_mod_u64(f, inv, q), q);
+    }
+    vec![r, pk - r]
+}
+
+// Roots of a mod p^k, the progressions base + j step for j < count
+struct LocalRoots {
+    bases: Vec<u64>,
+    step: u64,
+    count: u64,
+    // Takes a root mod p^k to its share of the CRT sum mod m
+    coefficient: u128,
+}
+
+impl LocalRoots {
+    fn new(a: u64, p: u64, k: u32, coefficient: u128) -> Self {
+        let pk = p.pow(k);
+        let a = a % pk;
+        // Roots of 0 are the multiples of p^ceil(k/2)
+        if a == 0 {
+            let step = p.pow(k.div_ceil(2));
+            return LocalRoots { bases: vec![0], step, count: pk / step, coefficient };
+        }
+        // a = p^v u needs v even, and then x = p^(v/2) y with y^2 = u mod p^(k-v)
+        let v = (0..).find(|&v| !a.is_multiple_of(p.pow(v + 1))).expect("a is not 0 mod p^k");
+        if v % 2 == 1 {
+            return LocalRoots { bases: vec![], step: pk, count: 1, coefficient };
+        }
+        let (scale, reduced) = (p.pow(v / 2), p.pow(k - v));
+        let bases = unit_sqrts_mod_prime_power(a / p.pow(v), p, k - v).into_iter().map(|y| scale * y).collect();
+        LocalRoots { bases, step: scale * reduced, count: scale, coefficient }
+    }
+
+    fn len(&self) -> u64 {
+        self.bases.len() as u64 * self.count
+    }
+
+    fn root(&self, i: u64) -> u64 {
+        self.bases[(i / self.count) as usize] + i % self.count * self.step
+    }
+}
+
+// Square roots mod m, produced lazily since 0 alone has about sqrt(m) of them
+// Each prime power of m has a digit picking one of its roots, and the digits turn like an odometer
+pub struct SquareRoots {
+    modulus: u128,
+    locals: Vec<LocalRoots>,
+    digits: Vec<u64>,
+    done: bool,
+}
+
+impl SquareRoots {
+    fn new(modulus: u128, locals: Vec<LocalRoots>) -> Self {
+        let done = locals.iter().any(|local| local.len() == 0);
+        SquareRoots { modulus, digits: vec![0; locals.len()], locals, done }
+    }
+}
+
+impl Iterator for SquareRoots {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.done {
+            return None;
+        }
+        let root = self.locals.iter().zip(&self.digits)
+            .fold(0, |x, (local, &i)| (x + local.root(i) as u128 * local.coefficient) % self.modulus);
+        self.done = true;
+        for (local, digit) in self.locals.iter().zip(&mut self.digits) {
+            *digit += 1;
+            if *digit < local.len() {
+                self.done = false;
+                break;
+            }
+            *digit = 0;
+        }
+        Some(root as u64)
+    }
+}
+
+// Every square root of a mod p^k, in no particular order
+pub fn sqrt_mod_prime_power(a: u64, p: u64, k: u32) -> SquareRoots {
+    SquareRoots::new(p.pow(k) as u128, vec![LocalRoots::new(a, p, k, 1)])
+}
+
+// Every square root of a mod m in no particular order, each prime power of m solved separately and recombined with the CRT
+pub fn sqrt_mod(a: u64, m: u64) -> SquareRoots {
+    let locals = factorize_u64(m).as_slice().iter().map(|&(p, k)| {
+        // (m / p^k) times its inverse mod p^k is 1 mod p^k and 0 mod the other prime powers
+        let (pk, rest) = (p.pow(k) as u128, (m / p.pow(k)) as u128);
+        let inverse = mod_inverse(rest % pk, pk).expect("Prime powers are coprime");
+        LocalRoots::new(a, p, k, rest * inverse % m as u128)
+    }).collect();
+    SquareRoots::new(m as u128, locals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factorize() {
+        assert!(factorize_u32(1).is_empty());
+        assert_eq!(factorize_u32(360).as_slice(), &[(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize_u32(u32::MAX).as_slice(), &[(3, 1), (5, 1), (17, 1), (257, 1), (65_537, 1)]);
+        assert_eq!(factorize_u64(576_460_959_300_714_796).to_vec(), vec![(2, 2), (7, 1), (11, 1), (108_943, 1), (17_179_869_209, 1)]);
+        assert_eq!(factorize_u64(((1 << 31) - 1) * ((1 << 31) - 1)).to_vec(), vec![((1 << 31) - 1, 2)]);
+        // F_7 = 2^128 + 1 is too big, but 2^128 - 1 = F_0 F_1 ... F_6
+        assert_eq!(factorize_u128(u128::MAX).to_vec(), vec![
+            (3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65_537, 1), (274_177, 1), (6_700_417, 1), (67_280_421_310_721, 1),
+        ]);
+        assert_eq!(factorize_u128((1 << 89) - 1).to_vec(), vec![((1 << 89) - 1, 1)]);
+        assert_eq!(factorize_u128(((1 << 61) - 1) * 1_000_000_007).to_vec(), vec![(1_000_000_007, 1), ((1 << 61) - 1, 1)]);
+
+        const SMALL: FactorizationU32 = factorize_u32(1_001);
+        assert_eq!(SMALL.len(), 3);
+    }
+
+    #[test]
+    fn test_arithmetic_functions() {
+        let phi: Vec<u32> = (1..=12).map(euler_phi_u32).collect();
+        assert_eq!(phi, vec![1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4]);
+        assert_eq!(euler_phi_u64(1_000_000_007), 1_000_000_006);
+        assert_eq!(euler_phi_u128(1 << 100), 1 << 99);
+
+        let lambda: Vec<u32> = (1..=16).map(carmichael_lambda_u32).collect();
+        assert_eq!(lambda, vec![1, 1, 2, 2, 4, 2, 6, 2, 6, 4, 10, 2, 12, 6, 4, 4]);
+        assert_eq!(carmichael_lambda_u64(561), 80);
+        assert_eq!(carmichael_lambda_u128(1 << 100), 1 << 98);
+
+        let mu: Vec<i8> = (1..=10).map(mobius_u32).collect();
+        assert_eq!(mu, vec![1, -1, -1, 0, -1, 1, -1, 0, 0, 1]);
+        assert_eq!(mobius_u128(((1 << 61) - 1) * 1_000_000_007 * 3), -1);
+        assert_eq!(mobius_u64(49), 0);
+
+        const PHI_OF_A_MILLION: u64 = euler_phi_u64(1_000_000);
+        assert_eq!(PHI_OF_A_MILLION, 400_000);
+    }
+
+    #[test]
+    fn test_jacobi() {
+        // Agrees with Euler's criterion for primes
+        for a in 0..23 {
+            let euler = crate::mod_ints::pow_mod_u64(a, 11, 23);
+            let expected = match euler { 0 => 0, 1 => 1, _ => -1 };
+            assert_eq!(jacobi_u32(a as u32, 23), expected);
+        }
+        assert_eq!(jacobi_u64(1001, 9907), -1);
+        assert_eq!(jacobi_u128(19, 45), 1);
+        assert_eq!(jacobi_u128(8, 21), -1);
+        assert_eq!(jacobi_u128(5, 21), 1);
+        assert_eq!(jacobi_u32(3, 9), 0);
+    }
+
+    #[test]
+    fn test_sqrt_mod_prime() {
+        // 998244353 - 1 = 119 * 2^23, the worst case for Tonelli-Shanks
+        const P: u64 = 998_244_353;
+        for a in [2, 3, 5, 123_456_789, P - 1] {
+            match sqrt_mod_prime(a, P) {
+                Some(r) => assert_eq!(mul_mod_u64(r, r, P), a),
+                None => assert_eq!(jacobi_u64(a, P), -1),
+            }
+        }
+        let p = 576_460_959_300_714_797;
+        let r = sqrt_mod_prime(mul_mod_u64(987_654_321, 987_654_321, p), p).unwrap();
+        assert!(r == 987_654_321 || r == p - 987_654_321);
+        assert_eq!(sqrt_mod_prime(3, 7), None);
+    }
+
+    #[test]
+    fn test_sqrt_mod_composite() {
+        let sorted = |roots: SquareRoots| {
+            let mut roots: Vec<u64> = roots.collect();
+            roots.sort_unstable();
+            roots
+        };
+        assert_eq!(sorted(sqrt_mod(1, 8)), vec![1, 3, 5, 7]);
+        assert_eq!(sorted(sqrt_mod(4, 15)), vec![2, 7, 8, 13]);
+        assert_eq!(sorted(sqrt_mod(0, 16)), vec![0, 4, 8, 12]);
+        assert_eq!(sorted(sqrt_mod(2, 9)), vec![]);
+        assert_eq!(sorted(sqrt_mod_prime_power(0, 3, 5)), vec![0, 27, 54, 81, 108, 135, 162, 189, 216]);
+
+        // Matches brute force over every residue of every small modulus
+        for m in 1..200u64 {
+            for a in 0..m {
+                let expected: Vec<u64> = (0..m).filter(|x| x * x % m == a).collect();
+                assert_eq!(sorted(sqrt_mod(a, m)), expected, "square roots of {} mod {}", a, m);
+            }
+        }
+
+        let m = 1_000_000_007 * 1_000_003;
+        let roots: Vec<u64> = sqrt_mod(mul_mod_u64(424_242, 424_242, m), m).collect();
+        assert_eq!(roots.len(), 4);
+        assert!(roots.contains(&424_242));
+        assert!(roots.iter().all(|&r| mul_mod_u64(r, r, m) == mul_mod_u64(424_242, 424_242, m)));
+
+        // 0 has 2^31 roots mod 2^62, taken lazily
+        let mut roots = sqrt_mod(0, 1 << 62);
+        assert_eq!(roots.nth(3), Some(3 << 31));
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(divisors_u32(1), vec![1]);
+        assert_eq!(divisors_u32(60), vec![1, 2, 3, 4, 5, 6, 10, 12, 15, 20, 30, 60]);
+        assert_eq!(divisors_u64(1_000_000_007), vec![1, 1_000_000_007]);
+        assert_eq!(divisors_u128(1 << 100).len(), 101);
+    }
+}
@@ -1,5 +1,6 @@
 use std::ops::{Add, Mul, Div};
 use std::num::NonZero;
+use crate::error::Error;
 
 pub trait OpFlag {}
 
@@ -17,6 +18,11 @@ pub trait Op<O: OpFlag> : Sized {
     fn op(&self, other: Self) -> Self;
 }
 
+// ? For types whose values carry context (a table, a modulus) that both operands have to share, op panics on a mismatch while try_op reports it
+pub trait TryOp<O: OpFlag> : Op<O> {
+    fn try_op(&self, other: Self) -> Result<Self, Error<Self>>;
+}
+
 impl<T> Op<ADD> for T where T: Add<Output=T> + Copy {
     fn op(&self, other: Self) -> Self {
         self.add(other)
@@ -79,6 +85,19 @@ where T: Div + Op<MUL> + From<u8>
 // ? Split into invertibility / divisibility?
 pub trait Inverse<O: OpFlag> : Op<O> {
     fn inverse(&self) -> Self;
+
+    // None when self has no inverse, for types where inverse can fail
+    fn try_inverse(&self) -> Option<Self> {
+        Some(self.inverse())
+    }
+}
+
+// ? Divisibility is what quasigroups have instead of inverses, None when the equation has no solution in the type
+pub trait Divisibility<O: OpFlag> : Op<O> {
+    // self \ b, the x with self * x = b
+    fn left_div(&self, b: Self) -> Option<Self>;
+    // self / a, the x with x * a = self
+    fn right_div(&self, a: Self) -> Option<Self>;
 }
 
 pub type BinaryOperation<T> = fn(T, T) -> T;
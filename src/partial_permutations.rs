@@ -0,0 +1,164 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::ops::*;
+use crate::set::{UniversalSet, Cardinality};
+use crate::algebraic_objects::*;
+use crate::permutations::Permutation;
+use crate::error::Error;
+
+// Injective partial map on {0..N-1}, None marks a point outside the domain
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PartialPermutation<const N: usize> {
+    map: Arc<Vec<Option<usize>>>,
+}
+
+impl<const N: usize> PartialPermutation<N> {
+    pub fn try_new(map: Vec<Option<usize>>) -> Result<PartialPermutation<N>, Error> {
+        if map.len() != N {
+            return Err(Error::InvalidPartialPermutation { map });
+        }
+
+        let mut visited = vec![false; N];
+        for &i in map.iter().flatten() {
+            if i >= N || visited[i] {
+                return Err(Error::InvalidPartialPermutation { map });
+            }
+            visited[i] = true;
+        }
+
+        Ok(PartialPermutation { map: Arc::new(map) })
+    }
+
+    pub fn new_unchecked(map: Vec<Option<usize>>) -> PartialPermutation<N> {
+        PartialPermutation { map: Arc::new(map) }
+    }
+
+    pub fn empty() -> PartialPermutation<N> {
+        PartialPermutation { map: Arc::new(vec![None; N]) }
+    }
+
+    pub fn apply(&self, point: usize) -> Option<usize> {
+        self.map[point]
+    }
+
+    pub fn domain(&self) -> Vec<usize> {
+        (0..N).filter(|&i| self.map[i].is_some()).collect()
+    }
+
+    pub fn image(&self) -> Vec<usize> {
+        let mut image: Vec<usize> = self.map.iter().flatten().cloned().collect();
+        image.sort();
+        image
+    }
+
+    // Size of the domain
+    pub fn rank(&self) -> usize {
+        self.map.iter().flatten().count()
+    }
+
+    // The unique y with x y x = x and y x y = y, which for partial permutations is the inverse partial map
+    pub fn semigroup_inverse(&self) -> PartialPermutation<N> {
+        let mut inv = vec![None; N];
+        for (i, x) in self.map.iter().enumerate() {
+            if let Some(x) = *x {
+                inv[x] = Some(i);
+            }
+        }
+        PartialPermutation { map: Arc::new(inv) }
+    }
+
+    // Idempotents of I_N are exactly the partial identities
+    pub fn is_idempotent(&self) -> bool {
+        self.map.iter().enumerate().all(|(i, x)| x.is_none_or(|x| x == i))
+    }
+}
+
+impl<const N: usize> From<Permutation<N>> for PartialPermutation<N> {
+    fn from(perm: Permutation<N>) -> Self {
+        PartialPermutation { map: Arc::new((0..N).map(|i| Some(perm.apply(i))).collect()) }
+    }
+}
+
+impl<const N: usize> Op<OP<1>> for PartialPermutation<N> {
+    fn op(&self, other: PartialPermutation<N>) -> Self {
+        let map = self.map.iter().map(|x| x.and_then(|x| other.map[x])).collect();
+        PartialPermutation { map: Arc::new(map) }
+    }
+}
+
+impl<const N: usize> Associative<OP<1>> for PartialPermutation<N> {}
+
+impl<const N: usize> Identity<OP<1>> for PartialPermutation<N> {
+    fn identity() -> Self {
+        PartialPermutation { map: Arc::new((0..N).map(Some).collect()) }
+    }
+}
+
+// Every partial permutation of {0..N-1}, in lexicographic order with None before Some(0)
+pub fn all_partial_permutations<const N: usize>() -> impl Iterator<Item = PartialPermutation<N>> {
+    std::iter::successors(Some(vec![None; N]), |map: &Vec<Option<usize>>| {
+        // Move the last point that can go to a larger unused image, and take the points after it out of the domain
+        (0..N).rev().find_map(|i| {
+            let start = map[i].map_or(0, |x| x + 1);
+            let image = (start..N).find(|&x| !map[..i].contains(&Some(x)))?;
+            let mut next = map[..i].to_vec();
+            next.push(Some(image));
+            next.resize(N, None);
+            Some(next)
+        })
+    }).map(PartialPermutation::new_unchecked)
+}
+
+// |I_N| = sum over k of C(N, k)^2 k!, each term being the last times (N - k)^2 / (k + 1)
+// Fails with OrderTooLarge from N = 19 on, where that outgrows usize
+pub fn symmetric_inverse_monoid<const N: usize>() -> Result<Monoid<OP<1>, PartialPermutation<N>>, Error> {
+    let (mut term, mut order) = (1u128, 1usize);
+    for k in 0..N {
+        term = term * (N - k) as u128 * (N - k) as u128 / (k + 1) as u128;
+        order = usize::try_from(term).ok().and_then(|term| order.checked_add(term)).ok_or(Error::OrderTooLarge)?;
+    }
+    let set = UniversalSet::new_enumerable(Cardinality::Finite(order), || Box::new(all_partial_permutations::<N>()));
+    Ok(Monoid::new_unchecked(Rc::new(set)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pp(map: Vec<Option<usize>>) -> PartialPermutation<3> {
+        PartialPermutation::try_new(map).unwrap()
+    }
+
+    #[test]
+    fn test_composition_and_inverse() {
+        let x = pp(vec![Some(1), None, Some(0)]);
+        let y = pp(vec![None, Some(2), Some(1)]);
+
+        assert_eq!(x.op(y.clone()), pp(vec![Some(2), None, None]));
+        assert_eq!(x.op(PartialPermutation::identity()), x);
+
+        let inv = x.semigroup_inverse();
+        assert_eq!(x.op(inv.clone()).op(x.clone()), x);
+        assert_eq!(inv.op(x.clone()).op(inv.clone()), inv);
+        assert!(x.op(inv).is_idempotent());
+
+        assert!(PartialPermutation::<3>::try_new(vec![Some(1), Some(1), None]).is_err());
+    }
+
+    #[test]
+    fn test_symmetric_inverse_monoid() {
+        let monoid = symmetric_inverse_monoid::<3>().unwrap();
+        assert_eq!(monoid.get_set().cardinality(), Cardinality::Finite(34));
+        assert_eq!(monoid.get_set().iter().unwrap().count(), 34);
+        assert_eq!(monoid.get_set().iter().unwrap().filter(|x| x.is_idempotent()).count(), 8);
+
+        let all: Vec<PartialPermutation<4>> = all_partial_permutations::<4>().collect();
+        assert_eq!(all.len(), 209);
+        assert!(all.windows(2).all(|w| w[0].map < w[1].map));
+        assert!(all.iter().all(|x| PartialPermutation::<4>::try_new(x.map.to_vec()).is_ok()));
+
+        assert_eq!(symmetric_inverse_monoid::<18>().unwrap().get_set().cardinality(), Cardinality::Finite(2_968_971_263_911_288_999));
+        assert!(matches!(symmetric_inverse_monoid::<19>(), Err(Error::OrderTooLarge)));
+    }
+}
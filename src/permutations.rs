@@ -3,34 +3,32 @@ use std::rc::Rc;
 use std::sync::{LazyLock, Arc, RwLock};
 use crate::ops::*;
 use crate::set::{UniversalSet, Cardinality};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::algebraic_objects::*;
+use crate::random::Rng;
+use crate::error::Error;
 use itertools::Itertools;
 
-#[derive(Clone, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Permutation<const N: usize> {
     perm: Arc<Vec<usize>>,
 }
 
 
 impl<const N: usize> Permutation<N> {
-    pub fn try_new(mut perm: Vec<usize>) -> Result<Permutation<N>, ()> {
+    pub fn try_new(perm: Vec<usize>) -> Result<Permutation<N>, Error> {
         let n = perm.len();
 
         if n != N {
-            return Err(());
+            return Err(Error::InvalidPermutation { perm });
         }
 
         let mut visited = vec![false; n];
 
 
         for &i in perm.iter() {
-            if i >= n {
-                return Err(());
-            }
-
-            if visited[i] {
-                return Err(());
+            if i >= n || visited[i] {
+                return Err(Error::InvalidPermutation { perm });
             }
             
             visited[i] = true;
@@ -47,30 +45,41 @@ impl<const N: usize> Permutation<N> {
         }
     }
 
+    // Panics on invalid cycles, see try_from_disjoint
     pub fn new_from_disjoint(cycles: Vec<Vec<usize>>) -> Permutation<N> {
-        let id: Permutation<N> = self::Identity::identity();
-        let mut res = (*id.perm).clone();
+        Self::try_from_disjoint(cycles).unwrap_or_else(|e| panic!("Invalid cycles: {}", e))
+    }
+
+    // Disjoint cycles over the points 1..=N
+    pub fn try_from_disjoint(cycles: Vec<Vec<usize>>) -> Result<Permutation<N>, Error> {
+        let mut res: Vec<usize> = (0..N).collect();
         let mut visited = vec![false; N];
         for cycle in cycles {
-            for i in 0..cycle.len() {
-                if visited[cycle[i] - 1] {
-                    panic!("Cycle has repeated elements");
+            for &point in cycle.iter() {
+                if point == 0 || point > N {
+                    return Err(Error::PointOutOfRange { point, n: N });
+                }
+                if visited[point - 1] {
+                    return Err(Error::RepeatedPoint { point });
                 }
+                visited[point - 1] = true;
+            }
+
+            for i in 0..cycle.len() {
                 res[cycle[i] - 1] = cycle[(i + 1) % cycle.len()] - 1;
-                visited[cycle[i] - 1] = true;
             }
         }
 
-        Permutation {
+        Ok(Permutation {
             perm: Arc::new(res),
-        }
+        })
     }
-}
-
 
-impl<const N: usize> PartialEq for Permutation<N> {
-    fn eq(&self, other: &Self) -> bool {
-        self.perm == other.perm
+    // Cycles that may overlap, multiplied in the order written with op, so (1 2)(2 3) applies (1 2) first
+    pub fn try_from_cycles(cycles: Vec<Vec<usize>>) -> Result<Permutation<N>, Error> {
+        cycles.into_iter().try_fold(Identity::identity(), |acc: Permutation<N>, cycle| {
+            Ok(acc.op(Self::try_from_disjoint(vec![cycle])?))
+        })
     }
 }
 
@@ -113,10 +122,517 @@ impl<const N: usize> Inverse<OP<1>> for Permutation<N> {
     }
 }
 
-pub fn symmetric_group<const N: usize>() -> Group<OP<1>, Permutation<N>> {
-    let set = UniversalSet::new(Cardinality::Finite(N));
-    Group::new_unchecked(Rc::new(set))
+// MARK: Ranking
+// None once n! no longer fits in a u128, which happens from 35! on
+fn factorial(n: usize) -> Option<u128> {
+    (1..=n as u128).try_fold(1u128, |acc, k| acc.checked_mul(k))
+}
+
+impl<const N: usize> Permutation<N> {
+    // Lehmer code: entry i counts the later positions holding a smaller value
+    pub fn lehmer_code(&self) -> Vec<usize> {
+        (0..N).map(|i| self.perm[i+1..].iter().filter(|&&x| x < self.perm[i]).count()).collect()
+    }
+
+    // Position of the permutation in the lexicographic ordering of S_N, starting at 0 for the identity
+    // None when the rank does not fit in a u128
+    pub fn rank(&self) -> Option<u128> {
+        self.lehmer_code().iter().enumerate().filter(|&(_, &c)| c > 0).try_fold(0u128, |acc, (i, &c)| {
+            acc.checked_add((c as u128).checked_mul(factorial(N - 1 - i)?)?)
+        })
+    }
+
+    // None for ranks past N! - 1, every u128 is a valid rank once N! outgrows it
+    pub fn unrank(mut rank: u128) -> Option<Permutation<N>> {
+        if factorial(N).is_some_and(|f| rank >= f) {
+            return None;
+        }
+
+        let mut remaining: Vec<usize> = (0..N).collect();
+        let mut perm = Vec::with_capacity(N);
+        for i in 0..N {
+            // A factorial too big for a u128 is bigger than the rank, so that digit is 0
+            match factorial(N - 1 - i) {
+                Some(f) => {
+                    perm.push(remaining.remove((rank / f) as usize));
+                    rank %= f;
+                }
+                None => perm.push(remaining.remove(0)),
+            }
+        }
+
+        Some(Permutation::new_unchecked(perm))
+    }
+
+    // Next permutation in lexicographic order, None for the last one (N-1, ..., 1, 0)
+    pub fn next_lex(&self) -> Option<Permutation<N>> {
+        let mut perm = (*self.perm).clone();
+        let i = (1..N).rev().find(|&i| perm[i - 1] < perm[i])? - 1;
+        let j = (i + 1..N).rev().find(|&j| perm[j] > perm[i])?;
+        perm.swap(i, j);
+        perm[i + 1..].reverse();
+        Some(Permutation::new_unchecked(perm))
+    }
+
+    // Previous permutation in lexicographic order, None for the identity
+    pub fn prev_lex(&self) -> Option<Permutation<N>> {
+        let mut perm = (*self.perm).clone();
+        let i = (1..N).rev().find(|&i| perm[i - 1] > perm[i])? - 1;
+        let j = (i + 1..N).rev().find(|&j| perm[j] < perm[i])?;
+        perm.swap(i, j);
+        perm[i + 1..].reverse();
+        Some(Permutation::new_unchecked(perm))
+    }
+}
+
+// MARK: Random
+impl<const N: usize> Permutation<N> {
+    // Uniformly random element of S_N
+    pub fn random(rng: &mut Rng) -> Permutation<N> {
+        let mut perm: Vec<usize> = (0..N).collect();
+        rng.shuffle(&mut perm);
+        Permutation::new_unchecked(perm)
+    }
+}
+
+pub struct LexPermutations<const N: usize> {
+    current: Option<Permutation<N>>,
+}
+
+impl<const N: usize> Iterator for LexPermutations<N> {
+    type Item = Permutation<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.take()?;
+        self.current = current.next_lex();
+        Some(current)
+    }
+}
+
+// Iterates over all of S_N in lexicographic order, starting from the identity
+pub fn lex_permutations<const N: usize>() -> LexPermutations<N> {
+    LexPermutations { current: Some(Identity::identity()) }
+}
+
+// MARK: Orbits and Blocks
+impl<const N: usize> Permutation<N> {
+    pub fn apply(&self, point: usize) -> usize {
+        self.perm[point]
+    }
+}
+
+// Group generated by a list of permutations, computed by closing the generators under composition
+pub fn generated_group<const N: usize>(generators: &[Permutation<N>]) -> Group<OP<1>, Permutation<N>> {
+    let id: Permutation<N> = Identity::identity();
+    let mut elements = HashSet::from([id.clone()]);
+    let mut queue = vec![id];
+    while let Some(x) = queue.pop() {
+        for g in generators {
+            let y = x.op(g.clone());
+            if elements.insert(y.clone()) {
+                queue.push(y);
+            }
+        }
+    }
+    Group::new_unchecked(Rc::new(elements))
+}
+
+pub fn orbit<const N: usize>(generators: &[Permutation<N>], point: usize) -> Vec<usize> {
+    let mut visited = vec![false; N];
+    visited[point] = true;
+    let mut orbit = vec![point];
+    let mut i = 0;
+    while i < orbit.len() {
+        for g in generators {
+            let y = g.apply(orbit[i]);
+            if !visited[y] {
+                visited[y] = true;
+                orbit.push(y);
+            }
+        }
+        i += 1;
+    }
+    orbit.sort();
+    orbit
 }
 
+pub fn is_transitive<const N: usize>(generators: &[Permutation<N>]) -> bool {
+    N == 0 || orbit(generators, 0).len() == N
+}
 
+// Partition of {0..N-1} into blocks, stored with each block sorted and the blocks ordered by their smallest point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockSystem<const N: usize> {
+    blocks: Vec<Vec<usize>>,
+    block_of: Vec<usize>,
+}
+
+impl<const N: usize> BlockSystem<N> {
+    fn from_labels(labels: &[usize]) -> BlockSystem<N> {
+        let mut blocks: Vec<Vec<usize>> = Vec::new();
+        let mut index_of_label = HashMap::new();
+        let mut block_of = vec![0; N];
+        for (x, label) in labels.iter().enumerate() {
+            let index = *index_of_label.entry(label).or_insert_with(|| {
+                blocks.push(Vec::new());
+                blocks.len() - 1
+            });
+            blocks[index].push(x);
+            block_of[x] = index;
+        }
+        BlockSystem { blocks, block_of }
+    }
+
+    pub fn blocks(&self) -> &[Vec<usize>] {
+        &self.blocks
+    }
 
+    pub fn block_of(&self, point: usize) -> usize {
+        self.block_of[point]
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    // Either a single block, or every point in its own block
+    pub fn is_trivial(&self) -> bool {
+        self.blocks.len() <= 1 || self.blocks.len() == N
+    }
+
+    // Every block of self lies inside a block of other
+    pub fn refines(&self, other: &BlockSystem<N>) -> bool {
+        (0..N).all(|x| other.block_of[x] == other.block_of[self.blocks[self.block_of[x]][0]])
+    }
+
+    // Permutation of the blocks induced by g, None if the blocks are not preserved or there are not K of them
+    pub fn induced_permutation<const K: usize>(&self, g: &Permutation<N>) -> Option<Permutation<K>> {
+        if self.num_blocks() != K {
+            return None;
+        }
+
+        let perm = self.blocks.iter().map(|block| self.block_of[g.apply(block[0])]).collect();
+        let preserves_blocks = self.blocks.iter().all(|block| {
+            block.iter().all(|&x| self.block_of[g.apply(x)] == self.block_of[g.apply(block[0])])
+        });
+
+        preserves_blocks.then(|| Permutation::new_unchecked(perm))
+    }
+
+    // Action of the group generated by the generators on the K blocks, as a permutation group
+    pub fn induced_action<const K: usize>(&self, generators: &[Permutation<N>]) -> Option<Group<OP<1>, Permutation<K>>> {
+        let induced: Option<Vec<Permutation<K>>> = generators.iter().map(|g| self.induced_permutation(g)).collect();
+        Some(generated_group(&induced?))
+    }
+}
+
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind { parent: (0..n).collect() }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        self.parent[a.max(b)] = a.min(b);
+        true
+    }
+}
+
+// Finest block system in which a and b share a block (Atkinson's algorithm), assumes the group is transitive
+pub fn minimal_block_system<const N: usize>(generators: &[Permutation<N>], a: usize, b: usize) -> BlockSystem<N> {
+    let mut classes = UnionFind::new(N);
+    let mut queue = Vec::new();
+    if classes.union(a, b) {
+        queue.push((a, b));
+    }
+
+    while let Some((x, y)) = queue.pop() {
+        for g in generators {
+            let (u, v) = (g.apply(x), g.apply(y));
+            if classes.union(u, v) {
+                queue.push((u, v));
+            }
+        }
+    }
+
+    let labels: Vec<usize> = (0..N).map(|x| classes.find(x)).collect();
+    BlockSystem::from_labels(&labels)
+}
+
+// All minimal non-trivial block systems of a transitive group, empty exactly when the group is primitive
+pub fn minimal_block_systems<const N: usize>(generators: &[Permutation<N>]) -> Vec<BlockSystem<N>> {
+    let mut candidates: Vec<BlockSystem<N>> = Vec::new();
+    for b in 1..N {
+        let system = minimal_block_system(generators, 0, b);
+        if system.num_blocks() > 1 && !candidates.contains(&system) {
+            candidates.push(system);
+        }
+    }
+
+    candidates.iter()
+        .filter(|s| !candidates.iter().any(|t| t != *s && t.refines(s)))
+        .cloned()
+        .collect()
+}
+
+pub fn is_primitive<const N: usize>(generators: &[Permutation<N>]) -> bool {
+    is_transitive(generators) && minimal_block_systems(generators).is_empty()
+}
+
+// MARK: Patterns
+impl<const N: usize> Permutation<N> {
+    // Length of the longest increasing subsequence of the one-line notation, by patience sorting
+    pub fn longest_increasing_subsequence(&self) -> usize {
+        let mut piles: Vec<usize> = Vec::new();
+        for &x in self.perm.iter() {
+            let i = piles.partition_point(|&top| top < x);
+            if i == piles.len() {
+                piles.push(x);
+            } else {
+                piles[i] = x;
+            }
+        }
+        piles.len()
+    }
+
+    pub fn longest_decreasing_subsequence(&self) -> usize {
+        let reversed: Vec<usize> = self.perm.iter().rev().cloned().collect();
+        Permutation::<N>::new_unchecked(reversed).longest_increasing_subsequence()
+    }
+
+    // Whether some K entries of the one-line notation appear in the same relative order as the pattern
+    pub fn contains_pattern<const K: usize>(&self, pattern: &Permutation<K>) -> bool {
+        if K > N {
+            return false;
+        }
+
+        // 12...k and k...21 reduce to longest monotone subsequences
+        if pattern.perm.iter().enumerate().all(|(i, &x)| i == x) {
+            return self.longest_increasing_subsequence() >= K;
+        }
+        if pattern.perm.iter().enumerate().all(|(i, &x)| i + x == K - 1) {
+            return self.longest_decreasing_subsequence() >= K;
+        }
+
+        (0..N).combinations(K).any(|positions| {
+            positions.iter().enumerate().all(|(i, &a)| {
+                positions[i + 1..].iter().enumerate().all(|(j, &b)| {
+                    (self.perm[a] < self.perm[b]) == (pattern.perm[i] < pattern.perm[i + 1 + j])
+                })
+            })
+        })
+    }
+
+    pub fn avoids_pattern<const K: usize>(&self, pattern: &Permutation<K>) -> bool {
+        !self.contains_pattern(pattern)
+    }
+}
+
+pub struct Avoiders<const N: usize, const K: usize> {
+    patterns: Vec<Permutation<K>>,
+    permutations: LexPermutations<N>,
+}
+
+impl<const N: usize, const K: usize> Iterator for Avoiders<N, K> {
+    type Item = Permutation<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let patterns = &self.patterns;
+        self.permutations.find(|p| patterns.iter().all(|pattern| p.avoids_pattern(pattern)))
+    }
+}
+
+// Permutations of S_N avoiding every one of the patterns, in lexicographic order
+pub fn avoiders<const N: usize, const K: usize>(patterns: Vec<Permutation<K>>) -> Avoiders<N, K> {
+    Avoiders { patterns, permutations: lex_permutations() }
+}
+
+impl<const N: usize> Divisibility<OP<1>> for Permutation<N> {
+    fn left_div(&self, b: Permutation<N>) -> Option<Self> {
+        Some(self.inverse().op(b))
+    }
+
+    fn right_div(&self, a: Permutation<N>) -> Option<Self> {
+        Some(self.op(a.inverse()))
+    }
+}
+
+// Fails with OrderTooLarge from N = 21 on, where N! outgrows usize
+pub fn symmetric_group<const N: usize>() -> Result<Group<OP<1>, Permutation<N>>, Error> {
+    let order = factorial(N).and_then(|f| usize::try_from(f).ok()).ok_or(Error::OrderTooLarge)?;
+    let set = UniversalSet::new_enumerable(Cardinality::Finite(order), || Box::new(lex_permutations::<N>()));
+    Ok(Group::new_unchecked(Rc::new(set)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_constructors() {
+        let p = Permutation::<3>::try_from_disjoint(vec![vec![1, 2, 3]]).unwrap();
+        assert_eq!(p, Permutation::new_unchecked(vec![1, 2, 0]));
+
+        assert_eq!(Permutation::<3>::try_from_disjoint(vec![vec![1, 2], vec![2, 3]]), Err(Error::RepeatedPoint { point: 2 }));
+        assert_eq!(Permutation::<3>::try_from_disjoint(vec![vec![1, 1]]), Err(Error::RepeatedPoint { point: 1 }));
+        assert_eq!(Permutation::<3>::try_from_disjoint(vec![vec![0, 1]]), Err(Error::PointOutOfRange { point: 0, n: 3 }));
+        assert_eq!(Permutation::<3>::try_from_disjoint(vec![vec![1, 0]]), Err(Error::PointOutOfRange { point: 0, n: 3 }));
+        assert_eq!(Permutation::<3>::try_from_disjoint(vec![vec![1, 4]]), Err(Error::PointOutOfRange { point: 4, n: 3 }));
+
+        // 1 -> 2 -> 3, 3 -> 2, 2 -> 1
+        let product = Permutation::<3>::try_from_cycles(vec![vec![1, 2], vec![2, 3]]).unwrap();
+        assert_eq!(product, Permutation::try_from_disjoint(vec![vec![1, 3, 2]]).unwrap());
+        assert!(Permutation::<3>::try_from_cycles(vec![vec![1, 2], vec![2, 5]]).is_err());
+    }
+
+    #[test]
+    fn test_rank_unrank() {
+        for k in 0..24 {
+            let p = Permutation::<4>::unrank(k).unwrap();
+            assert_eq!(p.rank(), Some(k));
+        }
+        assert!(Permutation::<4>::unrank(24).is_none());
+
+        // 34! still fits in a u128, 35! does not
+        let last = Permutation::<34>::new_unchecked((0..34).rev().collect());
+        assert_eq!(last.rank(), factorial(34).map(|f| f - 1));
+        assert_eq!(Permutation::<34>::unrank(factorial(34).unwrap()), None);
+        let p = Permutation::<40>::unrank(u128::MAX).unwrap();
+        assert_eq!(p.rank(), Some(u128::MAX));
+        assert_eq!(Permutation::<40>::new_unchecked((0..40).rev().collect()).rank(), None);
+        assert_eq!(Permutation::<3>::try_new(vec![0, 2, 2]), Err(Error::InvalidPermutation { perm: vec![0, 2, 2] }));
+        assert_eq!(Permutation::<4>::new_unchecked(vec![3, 2, 1, 0]).rank(), Some(23));
+        assert_eq!(Permutation::<4>::new_unchecked(vec![1, 0, 3, 2]).lehmer_code(), vec![1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_next_prev() {
+        let id: Permutation<3> = Identity::identity();
+        assert!(id.prev_lex().is_none());
+        assert_eq!(id.next_lex().unwrap(), Permutation::new_unchecked(vec![0, 2, 1]));
+
+        let last = Permutation::<3>::new_unchecked(vec![2, 1, 0]);
+        assert!(last.next_lex().is_none());
+        assert_eq!(last.prev_lex().unwrap(), Permutation::new_unchecked(vec![2, 0, 1]));
+    }
+
+    #[test]
+    fn test_lex_iteration() {
+        let all: Vec<Permutation<4>> = lex_permutations::<4>().collect();
+        assert_eq!(all.len(), 24);
+        assert!(all.iter().enumerate().all(|(k, p)| p.rank() == Some(k as u128)));
+
+        let group = symmetric_group::<4>().unwrap();
+        assert_eq!(group.order(), Cardinality::Finite(24));
+        assert_eq!(group.get_set().iter().unwrap().count(), 24);
+        assert_eq!(symmetric_group::<20>().unwrap().order(), Cardinality::Finite(2_432_902_008_176_640_000));
+        assert!(matches!(symmetric_group::<21>(), Err(Error::OrderTooLarge)));
+    }
+
+    #[test]
+    fn test_random_permutations() {
+        let mut rng = Rng::new(1);
+        let mut seen = HashMap::new();
+        for _ in 0..600 {
+            let p = Permutation::<3>::random(&mut rng);
+            assert!(Permutation::<3>::try_new((*p.perm).clone()).is_ok());
+            *seen.entry(p.rank()).or_insert(0) += 1;
+        }
+        assert_eq!(seen.len(), 6);
+        assert!(seen.values().all(|&c| c > 50));
+    }
+
+    #[test]
+    fn test_product_replacement() {
+        use crate::random::ProductReplacement;
+
+        // (1 2) and (1 2 3 4) generate all of S_4
+        let gens = vec![
+            Permutation::<4>::new_from_disjoint(vec![vec![1, 2]]),
+            Permutation::<4>::new_from_disjoint(vec![vec![1, 2, 3, 4]]),
+        ];
+        let pr = ProductReplacement::<OP<1>, _>::new(gens, Rng::new(3));
+        let seen: HashSet<u128> = pr.take(2000).filter_map(|p| p.rank()).collect();
+        assert_eq!(seen.len(), 24);
+    }
+
+    #[test]
+    fn test_blocks_of_square_symmetries() {
+        // D_4 acting on the corners of a square, the diagonals {1,3} and {2,4} form a block system
+        let gens = vec![
+            Permutation::<4>::new_from_disjoint(vec![vec![1, 2, 3, 4]]),
+            Permutation::<4>::new_from_disjoint(vec![vec![2, 4]]),
+        ];
+        assert!(is_transitive(&gens));
+        assert!(!is_primitive(&gens));
+
+        let systems = minimal_block_systems(&gens);
+        assert_eq!(systems.len(), 1);
+        assert_eq!(systems[0].blocks(), &[vec![0, 2], vec![1, 3]]);
+
+        let action = systems[0].induced_action::<2>(&gens).unwrap();
+        assert_eq!(action.order(), Cardinality::Finite(2));
+        assert!(systems[0].induced_action::<3>(&gens).is_none());
+        assert_eq!(generated_group(&gens).order(), Cardinality::Finite(8));
+    }
+
+    #[test]
+    fn test_primitive_groups() {
+        let s4 = vec![
+            Permutation::<4>::new_from_disjoint(vec![vec![1, 2]]),
+            Permutation::<4>::new_from_disjoint(vec![vec![1, 2, 3, 4]]),
+        ];
+        assert!(is_primitive(&s4));
+
+        let c5 = vec![Permutation::<5>::new_from_disjoint(vec![vec![1, 2, 3, 4, 5]])];
+        assert!(is_primitive(&c5));
+
+        let c6 = vec![Permutation::<6>::new_from_disjoint(vec![vec![1, 2, 3, 4, 5, 6]])];
+        let systems = minimal_block_systems(&c6);
+        assert_eq!(systems.len(), 2);
+        assert!(systems.iter().any(|s| s.num_blocks() == 3));
+        assert!(systems.iter().any(|s| s.num_blocks() == 2));
+    }
+
+    #[test]
+    fn test_pattern_containment() {
+        let p = Permutation::<5>::new_unchecked(vec![2, 4, 0, 3, 1]);
+        assert!(p.contains_pattern(&Permutation::<3>::new_unchecked(vec![1, 2, 0])));
+        assert!(p.contains_pattern(&Permutation::<2>::new_unchecked(vec![1, 0])));
+        assert!(p.contains_pattern(&Permutation::<3>::new_unchecked(vec![2, 1, 0])));
+        assert!(!p.contains_pattern(&Permutation::<3>::new_unchecked(vec![0, 1, 2])));
+        assert!(!p.contains_pattern(&Permutation::<6>::identity()));
+        assert_eq!(p.longest_increasing_subsequence(), 2);
+        assert_eq!(p.longest_decreasing_subsequence(), 3);
+    }
+
+    #[test]
+    fn test_avoiders() {
+        // Every single pattern of length 3 is avoided by a Catalan number of permutations
+        for pattern in lex_permutations::<3>() {
+            assert_eq!(avoiders::<5, 3>(vec![pattern.clone()]).count(), 42);
+        }
+
+        // Av(132, 213) has 2^(n-1) elements
+        let patterns = vec![
+            Permutation::<3>::new_unchecked(vec![0, 2, 1]),
+            Permutation::<3>::new_unchecked(vec![1, 0, 2]),
+        ];
+        assert_eq!(avoiders::<5, 3>(patterns).count(), 16);
+    }
+}
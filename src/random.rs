@@ -0,0 +1,131 @@
+use std::marker::PhantomData;
+use crate::ops::*;
+use crate::algebraic_objects::GroupElement;
+
+// MARK: Rng
+// SplitMix64, small and seedable so results can be reproduced without pulling in an outside crate
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in 0..n, rejection sampling avoids the modulo bias
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Cannot sample from an empty range");
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    pub fn below_usize(&mut self, n: usize) -> usize {
+        self.below(n as u64) as usize
+    }
+
+    pub fn coin(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    // Fisher-Yates
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below_usize(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+
+// MARK: Product Replacement
+const MIN_STATE_LEN: usize = 10;
+const BURN_IN: usize = 50;
+
+// Random elements of the group generated by the given generators, see Celler et al. "Generating random elements of a finite group"
+pub struct ProductReplacement<O: OpFlag, T>
+where T: GroupElement<O>
+{
+    state: Vec<T>,
+    accumulator: T,
+    rng: Rng,
+    _boo: PhantomData<O>
+}
+
+impl<O: OpFlag, T> ProductReplacement<O, T>
+where T: GroupElement<O>
+{
+    pub fn new(generators: Vec<T>, rng: Rng) -> Self {
+        let mut state = generators;
+        if state.is_empty() {
+            state.push(T::identity());
+        }
+
+        let k = state.len();
+        let len = MIN_STATE_LEN.max(k);
+        for i in k..len {
+            state.push(state[i % k].clone());
+        }
+
+        let mut res = ProductReplacement { state, accumulator: T::identity(), rng, _boo: PhantomData };
+        for _ in 0..BURN_IN {
+            res.step();
+        }
+        res
+    }
+
+    fn step(&mut self) {
+        let n = self.state.len();
+        let i = self.rng.below_usize(n);
+        let j = (i + 1 + self.rng.below_usize(n - 1)) % n;
+
+        let factor = if self.rng.coin() { self.state[j].clone() } else { self.state[j].inverse() };
+        self.state[i] = if self.rng.coin() { self.state[i].op(factor) } else { factor.op(self.state[i].clone()) };
+        self.accumulator = self.accumulator.op(self.state[i].clone());
+    }
+
+    pub fn random_element(&mut self) -> T {
+        self.step();
+        self.accumulator.clone()
+    }
+}
+
+impl<O: OpFlag, T> Iterator for ProductReplacement<O, T>
+where T: GroupElement<O>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.random_element())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        let a: Vec<u64> = (0..5).scan(Rng::new(42), |rng, _| Some(rng.next_u64())).collect();
+        let b: Vec<u64> = (0..5).scan(Rng::new(42), |rng, _| Some(rng.next_u64())).collect();
+        assert_eq!(a, b);
+
+        let mut rng = Rng::new(7);
+        assert!((0..1000).all(|_| rng.below(6) < 6));
+    }
+}
@@ -42,6 +42,7 @@ mod cardinality {
 
 pub use cardinality::*;
 use std::collections::HashSet;
+use crate::error::Error;
 
 pub trait SetT<T> {
     fn contains(&self, element: &T) -> bool;
@@ -50,24 +51,15 @@ pub trait SetT<T> {
     }
 
     fn cardinality(&self) -> Cardinality;
-    fn iter(&self) -> Iter<T> {
-        unimplemented!("Implement this for your set type")
-    }
-}
-
-
-struct Iter<T> {
-    _boo: PhantomData<T>
-}
 
-impl<T> Iterator for Iter<T> {
-    type Item = T;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        unimplemented!()
+    // ? Only sets that actually know their elements can be iterated, everything else returns None
+    fn iter(&self) -> Option<Box<dyn Iterator<Item = T> + '_>> {
+        None
     }
 }
 
+pub type Enumerator<T> = fn() -> Box<dyn Iterator<Item = T>>;
+
 const DEFAULT_IS_EMPTY_HINT: bool = false;
 
 use std::marker::PhantomData;
@@ -87,9 +79,21 @@ mod small_set {
         pub fn new(elements: Vec<T>) -> Self {
             FiniteSet { elements }
         }
+
+        // new trusts the elements to be distinct, otherwise the cardinality is off
+        pub fn try_new(elements: Vec<T>) -> Result<Self, Error<T>>
+        where T: PartialEq + Clone
+        {
+            for (i, x) in elements.iter().enumerate() {
+                if elements[..i].contains(x) {
+                    return Err(Error::DuplicateElement { element: x.clone() });
+                }
+            }
+            Ok(FiniteSet { elements })
+        }
     }
     
-    impl<T: Eq> SetT<T> for FiniteSet<T> {
+    impl<T: PartialEq + Clone> SetT<T> for FiniteSet<T> {
         fn contains(&self, element: &T) -> bool {
             self.elements.contains(element)
         }
@@ -102,7 +106,9 @@ mod small_set {
             Cardinality::Finite(self.elements.len())
         }
 
-        
+        fn iter(&self) -> Option<Box<dyn Iterator<Item = T> + '_>> {
+            Some(Box::new(self.elements.iter().cloned()))
+        }
     }
 }
 
@@ -142,7 +148,7 @@ mod hash_set {
     use std::collections::HashSet;
     use std::hash::Hash;
 
-    impl<T: Eq + Hash> SetT<T> for HashSet<T>{
+    impl<T: Eq + Hash + Clone> SetT<T> for HashSet<T>{
         fn contains(&self, element: &T) -> bool {
             self.contains(&element)
         }
@@ -154,6 +160,10 @@ mod hash_set {
         fn cardinality(&self) -> Cardinality {
             Cardinality::Finite(self.len())
         }
+
+        fn iter(&self) -> Option<Box<dyn Iterator<Item = T> + '_>> {
+            Some(Box::new(HashSet::iter(self).cloned()))
+        }
     }
 }
 
@@ -162,12 +172,18 @@ mod universe {
     use super::*;
     pub struct Universe<T> {
         cardinality: Cardinality,
+        enumerator: Option<Enumerator<T>>,
         _boo: PhantomData<T>
     }
 
     impl<T> Universe<T> {
         pub fn new(cardinality: Cardinality) -> Self {
-            Universe { cardinality , _boo: PhantomData }
+            Universe { cardinality, enumerator: None, _boo: PhantomData }
+        }
+
+        // The enumerator should yield every value of T exactly once, this is what lets the universe be iterated
+        pub fn new_enumerable(cardinality: Cardinality, enumerator: Enumerator<T>) -> Self {
+            Universe { cardinality, enumerator: Some(enumerator), _boo: PhantomData }
         }
     }
 
@@ -183,6 +199,10 @@ mod universe {
         fn cardinality(&self) -> Cardinality {
             self.cardinality
         }
+
+        fn iter(&self) -> Option<Box<dyn Iterator<Item = T> + '_>> {
+            self.enumerator.map(|enumerator| enumerator())
+        }
     }
 }
 
@@ -197,6 +217,10 @@ mod tests {
         let set = small_set::FiniteSet::new(vec![1, 2, 3]);
         assert!(set.contains(&1));
         assert!(!set.contains(&4));
+        assert_eq!(set.iter().unwrap().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        assert!(small_set::FiniteSet::try_new(vec![1, 2, 3]).is_ok());
+        assert!(matches!(small_set::FiniteSet::try_new(vec![1, 2, 1]), Err(Error::DuplicateElement { element: 1 })));
     }
 
     #[test]
@@ -0,0 +1,364 @@
+use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use crate::ops::*;
+use crate::set::SmallSet;
+use crate::algebraic_objects::*;
+use crate::error::Error;
+
+// Operation flag for structures given by a Cayley table
+pub type TABLE = OP<0>;
+
+// n x n multiplication table over the indices 0..n, row a column b holds a * b
+#[derive(Debug, PartialEq, Eq)]
+pub struct CayleyTable {
+    table: Vec<Vec<usize>>,
+    identity: Option<usize>,
+}
+
+impl CayleyTable {
+    pub fn try_new(table: Vec<Vec<usize>>) -> Result<Rc<CayleyTable>, Error<TableElement>> {
+        let n = table.len();
+        if table.iter().any(|row| row.len() != n) {
+            return Err(Error::MalformedTable { reason: "table must be square" });
+        }
+        if table.iter().flatten().any(|&x| x >= n) {
+            return Err(Error::MalformedTable { reason: "entries must be indices below the table size" });
+        }
+
+        let identity = (0..n).find(|&e| (0..n).all(|x| table[e][x] == x && table[x][e] == x));
+        Ok(Rc::new(CayleyTable { table, identity }))
+    }
+
+    pub fn size(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn product(&self, a: usize, b: usize) -> usize {
+        self.table[a][b]
+    }
+
+    pub fn identity(&self) -> Option<usize> {
+        self.identity
+    }
+
+    pub fn is_associative(&self) -> bool {
+        let n = self.size();
+        (0..n).all(|a| (0..n).all(|b| (0..n).all(|c| {
+            self.product(self.product(a, b), c) == self.product(a, self.product(b, c))
+        })))
+    }
+
+    // Every row and every column is a permutation of 0..n
+    pub fn is_latin_square(&self) -> bool {
+        self.find_indivisible().is_none()
+    }
+
+    // Some (a, b) for which a * x = b or x * a = b has no solution, when the table is not a Latin square
+    pub fn find_indivisible(&self) -> Option<(usize, usize)> {
+        let n = self.size();
+        (0..n).flat_map(|a| (0..n).map(move |b| (a, b))).find(|&(a, b)| {
+            !(0..n).any(|x| self.product(a, x) == b) || !(0..n).any(|x| self.product(x, a) == b)
+        })
+    }
+
+    // Two-sided inverse of a with respect to the identity
+    pub fn inverse_of(&self, a: usize) -> Option<usize> {
+        let e = self.identity?;
+        (0..self.size()).find(|&b| self.product(a, b) == e && self.product(b, a) == e)
+    }
+
+    pub fn has_inverses(&self) -> bool {
+        (0..self.size()).all(|a| self.inverse_of(a).is_some())
+    }
+
+    pub fn elements(self: &Rc<Self>) -> Vec<TableElement> {
+        (0..self.size()).map(|index| TableElement { table: Some(self.clone()), index }).collect()
+    }
+
+    pub fn element(self: &Rc<Self>, index: usize) -> TableElement {
+        assert!(index < self.size(), "Index outside the Cayley table");
+        TableElement { table: Some(self.clone()), index }
+    }
+}
+
+// Element of a table-defined structure, multiplied by looking up its shared table
+// Identity::identity has no table to look in, so it returns a formal identity that acts as the table's identity
+#[derive(Debug, Clone)]
+pub struct TableElement {
+    table: Option<Rc<CayleyTable>>,
+    index: usize,
+}
+
+impl TableElement {
+    // None for the formal identity
+    pub fn index(&self) -> Option<usize> {
+        self.table.as_ref().map(|_| self.index)
+    }
+
+    pub fn is_formal_identity(&self) -> bool {
+        self.table.is_none()
+    }
+
+    // Index in the given table, with the formal identity standing for the table's identity
+    fn resolve(&self, table: &Rc<CayleyTable>) -> Option<usize> {
+        match &self.table {
+            Some(t) => Rc::ptr_eq(t, table).then_some(self.index),
+            None => table.identity(),
+        }
+    }
+}
+
+impl PartialEq for TableElement {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.table, &other.table) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b) && self.index == other.index,
+            (Some(t), None) => t.identity == Some(self.index),
+            (None, Some(t)) => t.identity == Some(other.index),
+            (None, None) => true,
+        }
+    }
+}
+
+impl Eq for TableElement {}
+
+// Equal elements have to hash alike, and the formal identity equals whatever identity its table has,
+// so every identity hashes as the same marker and other elements by their table and index
+impl Hash for TableElement {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match &self.table {
+            Some(t) if t.identity != Some(self.index) => Some((Rc::as_ptr(t), self.index)).hash(state),
+            _ => None::<(*const CayleyTable, usize)>.hash(state),
+        }
+    }
+}
+
+impl Display for TableElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.table {
+            Some(_) => write!(f, "{}", self.index),
+            None => write!(f, "e"),
+        }
+    }
+}
+
+impl Op<TABLE> for TableElement {
+    fn op(&self, other: TableElement) -> Self {
+        match (&self.table, &other.table) {
+            (Some(a), Some(b)) => {
+                assert!(Rc::ptr_eq(a, b), "Elements belong to different Cayley tables");
+                TableElement { table: Some(a.clone()), index: a.product(self.index, other.index) }
+            },
+            (Some(_), None) => self.clone(),
+            (None, _) => other,
+        }
+    }
+}
+
+impl TryOp<TABLE> for TableElement {
+    fn try_op(&self, other: TableElement) -> Result<Self, Error<Self>> {
+        match (&self.table, &other.table) {
+            (Some(a), Some(b)) if !Rc::ptr_eq(a, b) => Err(Error::IncompatibleOperands { a: self.clone(), b: other }),
+            _ => Ok(self.op(other)),
+        }
+    }
+}
+
+// Only promised by the semigroup, monoid and group constructors, which check associativity
+impl Associative<TABLE> for TableElement {}
+
+impl Identity<TABLE> for TableElement {
+    fn identity() -> Self {
+        TableElement { table: None, index: 0 }
+    }
+}
+
+// Only table_group guarantees every element has an inverse, elsewhere use try_inverse
+impl Inverse<TABLE> for TableElement {
+    fn inverse(&self) -> Self {
+        self.try_inverse().expect("Element has no inverse in its Cayley table")
+    }
+
+    fn try_inverse(&self) -> Option<Self> {
+        match &self.table {
+            Some(t) => Some(TableElement { table: Some(t.clone()), index: t.inverse_of(self.index)? }),
+            None => Some(self.clone()),
+        }
+    }
+}
+
+// e \ e = e / e = e even when both are formal identities, with no table to look in
+impl Divisibility<TABLE> for TableElement {
+    fn left_div(&self, b: TableElement) -> Option<Self> {
+        let Some(table) = self.table.as_ref().or(b.table.as_ref()).cloned() else { return Some(self.clone()) };
+        let (a, b) = (self.resolve(&table)?, b.resolve(&table)?);
+        let x = (0..table.size()).find(|&x| table.product(a, x) == b)?;
+        Some(TableElement { table: Some(table), index: x })
+    }
+
+    fn right_div(&self, a: TableElement) -> Option<Self> {
+        let Some(table) = self.table.as_ref().or(a.table.as_ref()).cloned() else { return Some(self.clone()) };
+        let (b, a) = (self.resolve(&table)?, a.resolve(&table)?);
+        let x = (0..table.size()).find(|&x| table.product(x, a) == b)?;
+        Some(TableElement { table: Some(table), index: x })
+    }
+}
+
+fn table_set(table: &Rc<CayleyTable>) -> Rc<SmallSet<TableElement>> {
+    Rc::new(SmallSet::new(table.elements()))
+}
+
+pub fn table_magma(table: Vec<Vec<usize>>) -> Result<Magma<TABLE, TableElement>, Error<TableElement>> {
+    let table = CayleyTable::try_new(table)?;
+    Magma::try_new(table_set(&table))
+}
+
+pub fn table_semigroup(table: Vec<Vec<usize>>) -> Result<Semigroup<TABLE, TableElement>, Error<TableElement>> {
+    let table = CayleyTable::try_new(table)?;
+    Semigroup::try_new(table_set(&table))
+}
+
+pub fn table_quasigroup(table: Vec<Vec<usize>>) -> Result<Quasigroup<TABLE, TableElement>, Error<TableElement>> {
+    let table = CayleyTable::try_new(table)?;
+    Quasigroup::try_new(table_set(&table))
+}
+
+pub fn table_loop(table: Vec<Vec<usize>>) -> Result<Loop<TABLE, TableElement>, Error<TableElement>> {
+    let table = CayleyTable::try_new(table)?;
+    Loop::try_new(table_set(&table))
+}
+
+pub fn table_monoid(table: Vec<Vec<usize>>) -> Result<Monoid<TABLE, TableElement>, Error<TableElement>> {
+    let table = CayleyTable::try_new(table)?;
+    Monoid::try_new(table_set(&table))
+}
+
+pub fn table_group(table: Vec<Vec<usize>>) -> Result<Group<TABLE, TableElement>, Error<TableElement>> {
+    let table = CayleyTable::try_new(table)?;
+    Group::try_new(table_set(&table))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::Cardinality;
+    use std::collections::HashSet;
+
+    fn klein_four() -> Vec<Vec<usize>> {
+        vec![
+            vec![0, 1, 2, 3],
+            vec![1, 0, 3, 2],
+            vec![2, 3, 0, 1],
+            vec![3, 2, 1, 0],
+        ]
+    }
+
+    #[test]
+    fn test_table_group() {
+        let group = table_group(klein_four()).unwrap();
+        assert_eq!(group.order(), Cardinality::Finite(4));
+
+        let elements: Vec<TableElement> = group.get_set().iter().unwrap().collect();
+        assert!(elements.iter().skip(1).all(|x| group.order_of_element(x.clone()) == Some(2)));
+        assert_eq!(elements[1].op(elements[2].clone()), elements[3]);
+        assert_eq!(elements[0], TableElement::identity());
+        assert_eq!(elements[3].inverse(), elements[3]);
+
+        let other = table_group(klein_four()).unwrap().get_set().iter().unwrap().next().unwrap();
+        assert!(matches!(elements[1].try_op(other), Err(Error::IncompatibleOperands { .. })));
+    }
+
+    #[test]
+    fn test_hashing_and_partial_inverses() {
+        // {0, 1} under multiplication, 1 is the identity and 0 has no inverse
+        let monoid = table_monoid(vec![vec![0, 0], vec![0, 1]]).unwrap();
+        let elements: Vec<TableElement> = monoid.get_set().iter().unwrap().collect();
+        assert_eq!(elements[0].try_inverse(), None);
+        assert_eq!(elements[1].try_inverse(), Some(elements[1].clone()));
+
+        let set: HashSet<TableElement> = [TableElement::identity(), elements[1].clone(), elements[0].clone()].into();
+        assert_eq!(set.len(), 2);
+        assert_eq!(monoid.is_band(), Some(true));
+        assert_eq!(monoid.greens_relations().unwrap().d_classes().len(), 2);
+    }
+
+    #[test]
+    fn test_axioms_are_checked() {
+        // x * y = x is associative but has no identity
+        let left_zero = vec![vec![0, 0], vec![1, 1]];
+        assert!(table_semigroup(left_zero.clone()).is_ok());
+        assert!(matches!(table_monoid(left_zero.clone()), Err(Error::MissingIdentity)));
+        assert!(table_quasigroup(left_zero).is_err());
+
+        // x * y = x - y mod 3 is a Latin square but not associative
+        let subtraction = vec![vec![0, 2, 1], vec![1, 0, 2], vec![2, 1, 0]];
+        assert!(table_quasigroup(subtraction.clone()).is_ok());
+        assert!(matches!(table_semigroup(subtraction.clone()), Err(Error::NotAssociative { .. })));
+        assert!(table_group(subtraction).is_err());
+
+        // {0, 1} under multiplication is a monoid, 0 has no inverse
+        let multiplication = vec![vec![0, 0], vec![0, 1]];
+        assert!(table_monoid(multiplication.clone()).is_ok());
+        let zero = CayleyTable::try_new(multiplication.clone()).unwrap().element(0);
+        assert!(matches!(table_group(multiplication.clone()), Err(Error::NotInvertible { element }) if element.index() == zero.index()));
+        assert_eq!(zero.try_inverse(), None);
+        let set = table_monoid(multiplication).unwrap().get_set();
+        assert!(matches!(check_inverses::<TABLE, _>(set.as_ref()), Err(Error::NotInvertible { .. })));
+
+        assert!(matches!(table_loop(vec![vec![0, 2, 1], vec![1, 0, 2], vec![2, 1, 0]]), Err(Error::MissingIdentity)));
+
+        assert!(table_magma(vec![vec![0, 1], vec![2, 0]]).is_err());
+        assert!(table_magma(vec![vec![0, 1]]).is_err());
+    }
+
+    // Smallest loop that is not a group, with identity 0
+    fn non_associative_loop() -> Vec<Vec<usize>> {
+        vec![
+            vec![0, 1, 2, 3, 4],
+            vec![1, 0, 3, 4, 2],
+            vec![2, 4, 0, 1, 3],
+            vec![3, 2, 4, 0, 1],
+            vec![4, 3, 1, 2, 0],
+        ]
+    }
+
+    #[test]
+    fn test_division() {
+        let quasigroup = table_quasigroup(vec![vec![0, 2, 1], vec![1, 0, 2], vec![2, 1, 0]]).unwrap();
+        let elements: Vec<TableElement> = quasigroup.get_set().iter().unwrap().collect();
+        for a in &elements {
+            for b in &elements {
+                assert!(quasigroup.test_divisibility(a.clone(), b.clone()));
+                assert_eq!(a.op(a.left_div(b.clone()).unwrap()), *b);
+                assert_eq!(b.right_div(a.clone()).unwrap().op(a.clone()), *b);
+            }
+        }
+
+        let sub = Quasigroup::<TABLE, _>::new_unchecked(Rc::new(SmallSet::new(vec![elements[0].clone()])));
+        assert_eq!(sub.is_subquasigroup_of(&quasigroup), Some(true));
+        let not_sub = Quasigroup::<TABLE, _>::new_unchecked(Rc::new(SmallSet::new(vec![elements[0].clone(), elements[1].clone()])));
+        assert_eq!(not_sub.is_subquasigroup_of(&quasigroup), Some(false));
+    }
+
+    #[test]
+    fn test_loops() {
+        let lp = table_loop(non_associative_loop()).unwrap();
+        assert!(table_group(non_associative_loop()).is_err());
+        assert_eq!(lp.is_left_bol(), Some(false));
+        assert_eq!(lp.is_moufang(), Some(false));
+
+        // Every group is a Moufang loop
+        let group = table_loop(klein_four()).unwrap();
+        assert_eq!((group.is_left_bol(), group.is_right_bol(), group.is_moufang()), (Some(true), Some(true), Some(true)));
+
+        // Two elements of this loop generate all of it, one element x of either only {e, x}
+        let elements: Vec<TableElement> = lp.get_set().iter().unwrap().collect();
+        let generated = Loop::<TABLE, _>::new_from_generators(Rc::new(SmallSet::new(vec![elements[1].clone(), elements[2].clone()]))).unwrap();
+        assert_eq!(generated.get_set().cardinality(), Cardinality::Finite(5));
+        let x = group.get_set().iter().unwrap().nth(2).unwrap();
+        let generated = Loop::<TABLE, _>::new_from_generators(Rc::new(SmallSet::new(vec![x.clone()]))).unwrap();
+        assert_eq!(generated.get_set().cardinality(), Cardinality::Finite(2));
+        assert!(Loop::<TABLE, _>::try_new(generated.get_set()).is_ok());
+    }
+}
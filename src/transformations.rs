@@ -0,0 +1,155 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::ops::*;
+use crate::set::{UniversalSet, Cardinality};
+use crate::algebraic_objects::*;
+use crate::permutations::Permutation;
+use crate::error::Error;
+
+// Arbitrary map {0..N-1} -> {0..N-1}, stored in one-line notation like Permutation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Transformation<const N: usize> {
+    map: Arc<Vec<usize>>,
+}
+
+impl<const N: usize> Transformation<N> {
+    pub fn try_new(map: Vec<usize>) -> Result<Transformation<N>, Error> {
+        if map.len() != N || map.iter().any(|&i| i >= N) {
+            return Err(Error::InvalidTransformation { map });
+        }
+
+        Ok(Transformation { map: Arc::new(map) })
+    }
+
+    pub fn new_unchecked(map: Vec<usize>) -> Transformation<N> {
+        Transformation { map: Arc::new(map) }
+    }
+
+    // Constant map sending every point to value, which has to be a point itself
+    pub fn constant(value: usize) -> Result<Transformation<N>, Error> {
+        Transformation::try_new(vec![value; N])
+    }
+
+    pub fn apply(&self, point: usize) -> usize {
+        self.map[point]
+    }
+
+    pub fn image(&self) -> Vec<usize> {
+        let mut image = (*self.map).clone();
+        image.sort();
+        image.dedup();
+        image
+    }
+
+    // Size of the image
+    pub fn rank(&self) -> usize {
+        self.image().len()
+    }
+
+    pub fn is_permutation(&self) -> bool {
+        self.rank() == N
+    }
+
+    pub fn to_permutation(&self) -> Option<Permutation<N>> {
+        Permutation::try_new((*self.map).clone()).ok()
+    }
+}
+
+impl<const N: usize> From<Permutation<N>> for Transformation<N> {
+    fn from(perm: Permutation<N>) -> Self {
+        Transformation { map: Arc::new((0..N).map(|i| perm.apply(i)).collect()) }
+    }
+}
+
+impl<const N: usize> Op<OP<1>> for Transformation<N> {
+    fn op(&self, other: Transformation<N>) -> Self {
+        let map = self.map.iter().map(|&x| other.map[x]).collect();
+        Transformation { map: Arc::new(map) }
+    }
+}
+
+impl<const N: usize> Associative<OP<1>> for Transformation<N> {}
+
+impl<const N: usize> Identity<OP<1>> for Transformation<N> {
+    fn identity() -> Self {
+        Transformation { map: Arc::new((0..N).collect()) }
+    }
+}
+
+// All N^N transformations, counting up in base N with the first point as the lowest digit
+pub fn all_transformations<const N: usize>() -> impl Iterator<Item = Transformation<N>> {
+    std::iter::successors(Some(vec![0; N]), |map| {
+        let mut next = map.clone();
+        for digit in next.iter_mut() {
+            *digit += 1;
+            if *digit < N {
+                return Some(next);
+            }
+            *digit = 0;
+        }
+        None
+    }).map(Transformation::new_unchecked)
+}
+
+// Fails with OrderTooLarge from N = 16 on, where N^N outgrows usize
+pub fn full_transformation_monoid<const N: usize>() -> Result<Monoid<OP<1>, Transformation<N>>, Error> {
+    let order = N.checked_pow(N as u32).ok_or(Error::OrderTooLarge)?;
+    let set = UniversalSet::new_enumerable(Cardinality::Finite(order), || Box::new(all_transformations::<N>()));
+    Ok(Monoid::new_unchecked(Rc::new(set)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::SmallSet;
+
+    fn t(map: Vec<usize>) -> Transformation<3> {
+        Transformation::try_new(map).unwrap()
+    }
+
+    #[test]
+    fn test_composition() {
+        let x = t(vec![1, 1, 2]);
+        let y = t(vec![2, 0, 0]);
+        assert_eq!(x.op(y.clone()), t(vec![0, 0, 0]));
+        assert_eq!(y.op(x.clone()), t(vec![2, 1, 1]));
+        assert_eq!(x.op(Transformation::identity()), x);
+        assert_eq!(x.rank(), 2);
+        assert!(Transformation::<3>::try_new(vec![0, 3, 1]).is_err());
+        assert!(Transformation::<3>::constant(3).is_err());
+    }
+
+    #[test]
+    fn test_full_transformation_monoid() {
+        let monoid = full_transformation_monoid::<3>().unwrap();
+        assert_eq!(monoid.get_set().cardinality(), Cardinality::Finite(27));
+        assert_eq!(monoid.get_set().iter().unwrap().filter(|x| x.is_permutation()).count(), 6);
+        assert_eq!(full_transformation_monoid::<15>().unwrap().get_set().cardinality(), Cardinality::Finite(437_893_890_380_859_375));
+        assert!(matches!(full_transformation_monoid::<16>(), Err(Error::OrderTooLarge)));
+        assert_eq!(monoid.is_regular(), Some(true));
+        assert_eq!(monoid.is_band(), Some(false));
+
+        // 0 -> 1 -> 2 -> 1 reaches the cycle (1 2) after one step
+        let x = t(vec![1, 2, 1]);
+        assert_eq!(monoid.index_and_period(x.clone()), Some((1, 2)));
+        assert_eq!(monoid.idempotent_power(x), Some(t(vec![2, 1, 2])));
+        let y = t(vec![1, 2, 2]);
+        assert_eq!(monoid.index_and_period(y.clone()), Some((2, 1)));
+        assert_eq!(monoid.idempotent_power(y), Some(t(vec![2, 2, 2])));
+    }
+
+    #[test]
+    fn test_generated_semigroups() {
+        // A transposition, a 3-cycle and a rank 2 map generate all of T_3
+        let gens = SmallSet::new(vec![t(vec![1, 0, 2]), t(vec![1, 2, 0]), t(vec![0, 0, 2])]);
+        let monoid = Monoid::<OP<1>, _>::new_from_generators(Rc::new(gens)).unwrap();
+        assert_eq!(monoid.get_set().cardinality(), Cardinality::Finite(27));
+
+        // The constant maps alone form a semigroup of right zeros without an identity
+        let gens = SmallSet::new(vec![Transformation::<3>::constant(0).unwrap(), Transformation::<3>::constant(1).unwrap()]);
+        let semigroup = Semigroup::<OP<1>, _>::new_from_generators(Rc::new(gens)).unwrap();
+        assert_eq!(semigroup.get_set().cardinality(), Cardinality::Finite(2));
+        assert!(!semigroup.get_set().contains(&Transformation::identity()));
+    }
+}
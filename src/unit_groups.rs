@@ -0,0 +1,240 @@
+use crate::{ops::*, mod_ints::*, util::*};
+use crate::discrete_log::{LogElement, pow};
+use crate::number_theory::{factorize_u128, euler_phi_u128, carmichael_lambda_u128};
+
+// MARK: Units
+// Multiplicative groups of integers mod a modulus, orders come from factoring phi(M) rather than stepping through powers
+// Everything is counted in u128 so the same code covers every width of modulus
+pub trait Units: LogElement<MUL> {
+    fn modulus() -> u128;
+
+    // None when n is not a unit
+    fn from_residue(n: u128) -> Option<Self>;
+
+    fn unit_group_order() -> u128 {
+        euler_phi_u128(Self::modulus())
+    }
+
+    // The exponent of the group, every order divides it
+    fn unit_group_exponent() -> u128 {
+        carmichael_lambda_u128(Self::modulus())
+    }
+
+    // Residue types accept only units, fields also hold 0
+    fn is_unit(&self) -> bool {
+        true
+    }
+
+    // None for non-units, which have no multiplicative order
+    fn multiplicative_order(&self) -> Option<u128> {
+        if !self.is_unit() {
+            return None;
+        }
+        let mut order = Self::unit_group_exponent();
+        for &(q, _) in factorize_u128(order).as_slice() {
+            while order.is_multiple_of(q) && pow::<MUL, _>(*self, order / q) == Self::identity() {
+                order /= q;
+            }
+        }
+        Some(order)
+    }
+
+    fn is_primitive_root(&self) -> bool {
+        self.multiplicative_order() == Some(Self::unit_group_order())
+    }
+
+    // (Z/M)^x as a product of cyclic groups, one generator and its order per factor, trivial factors left out
+    // Uses (Z/M)^x = prod (Z/p^k)^x, which is cyclic for odd p and C2 x C2^(k-2) generated by -1 and 5 for p = 2, k >= 3
+    fn cyclic_decomposition() -> Vec<(Self, u128)> {
+        let m = Self::modulus();
+        let mut factors = Vec::new();
+        for &(p, k) in factorize_u128(m).as_slice() {
+            let pk = p.pow(k);
+            let local = match (p, k) {
+                (2, 1) => vec![],
+                (2, 2) => vec![(3, 2)],
+                (2, _) => vec![(pk - 1, 2), (5, pk / 4)],
+                _ => vec![(odd_prime_power_root(p, k), pk / p * (p - 1))],
+            };
+            for (g, order) in local {
+                // g mod p^k and 1 mod the rest of M
+                let (x, _) = crt_pairs(&[(g, pk), (1, m / pk)]).expect("Prime powers of M are coprime");
+                factors.push((Self::from_residue(x).expect("Generators are units"), order));
+            }
+        }
+        factors
+    }
+
+    // A generator of (Z/M)^x, which is cyclic exactly when it has at most one nontrivial factor
+    fn primitive_root() -> Option<Self> {
+        let mut factors = Self::cyclic_decomposition();
+        match factors.len() {
+            0 => Some(Self::identity()),
+            1 => factors.pop().map(|(g, _)| g),
+            _ => None,
+        }
+    }
+}
+
+// Primitive root mod p^k for an odd prime p, a root mod p that is not one mod p^2 is one for every power of p
+fn odd_prime_power_root(p: u128, k: u32) -> u128 {
+    let factors = factorize_u128(p - 1);
+    let g = (2..p)
+        .find(|&g| factors.as_slice().iter().all(|&(q, _)| pow_mod_u128(g, (p - 1) / q, p) != 1))
+        .expect("Every odd prime has a primitive root");
+    // p^2 divides M when k > 1, so it fits
+    if k > 1 && pow_mod_u128(g, p - 1, p * p) == 1 { g + p } else { g }
+}
+
+impl<const M: u32> Units for U32CoprimeMod<M> {
+    fn modulus() -> u128 {
+        M as u128
+    }
+
+    fn from_residue(n: u128) -> Option<Self> {
+        Self::try_new((n % M as u128) as u32).ok()
+    }
+}
+
+impl<const M: u64> Units for U64CoprimeMod<M> {
+    fn modulus() -> u128 {
+        M as u128
+    }
+
+    fn from_residue(n: u128) -> Option<Self> {
+        Self::try_new((n % M as u128) as u64).ok()
+    }
+}
+
+impl<const M: u128> Units for U128CoprimeMod<M> {
+    fn modulus() -> u128 {
+        M
+    }
+
+    fn from_residue(n: u128) -> Option<Self> {
+        Self::try_new(n).ok()
+    }
+}
+
+impl<const P: u32> Units for U32Field<P> where Assert<{is_prime(P)}>: IsTrue {
+    fn modulus() -> u128 {
+        P as u128
+    }
+
+    fn from_residue(n: u128) -> Option<Self> {
+        let n = (n % P as u128) as u32;
+        (n != 0).then_some(U32Field(n))
+    }
+
+    fn is_unit(&self) -> bool {
+        !self.0.is_multiple_of(P)
+    }
+}
+
+impl<const P: u64> Units for U64Field<P> where Assert<{is_prime_u64(P)}>: IsTrue {
+    fn modulus() -> u128 {
+        P as u128
+    }
+
+    fn from_residue(n: u128) -> Option<Self> {
+        let n = (n % P as u128) as u64;
+        (n != 0).then_some(U64Field(n))
+    }
+
+    fn is_unit(&self) -> bool {
+        !self.0.is_multiple_of(P)
+    }
+}
+
+impl<const P: u128> Units for U128Field<P> where Assert<{is_prime_u128(P)}>: IsTrue {
+    fn modulus() -> u128 {
+        P
+    }
+
+    fn from_residue(n: u128) -> Option<Self> {
+        let n = n % P;
+        (n != 0).then_some(U128Field(n))
+    }
+
+    fn is_unit(&self) -> bool {
+        !self.0.is_multiple_of(P)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_multiplicative_order() {
+        let two = U64CoprimeMod::<1_000_000_007>::try_new(2).unwrap();
+        assert_eq!(two.multiplicative_order(), Some(500_000_003));
+        assert!(!two.is_primitive_root());
+        assert!(U64CoprimeMod::<1_000_000_007>::try_new(5).unwrap().is_primitive_root());
+
+        const P: u64 = 576_460_959_300_714_797;
+        let g = U64Field::<P>(2);
+        assert_eq!(g.multiplicative_order(), Some((P - 1) as u128));
+        assert_eq!(U64Field::<P>::primitive_root(), Some(g));
+        assert_eq!(U32Field::<65_537>::primitive_root(), Some(U32Field(3)));
+        assert_eq!(U32CoprimeMod::<12>::try_new(5).unwrap().multiplicative_order(), Some(2));
+
+        // 0 is in the field but not a unit
+        assert_eq!(U32Field::<7>(0).multiplicative_order(), None);
+        assert!(!U32Field::<7>(0).is_primitive_root());
+        assert_eq!(U64Field::<1_000_000_007>(0).multiplicative_order(), None);
+        assert!(!U128Field::<1_000_000_007>(0).is_primitive_root());
+    }
+
+    #[test]
+    fn test_primitive_roots() {
+        assert_eq!(U32CoprimeMod::<1>::primitive_root(), Some(U32CoprimeMod::identity()));
+        assert_eq!(U32CoprimeMod::<15>::primitive_root(), None);
+        assert_eq!(U32CoprimeMod::<16>::primitive_root(), None);
+        assert!(U32CoprimeMod::<4>::primitive_root().unwrap().is_primitive_root());
+        assert!(U32CoprimeMod::<18>::primitive_root().unwrap().is_primitive_root());
+        // 5 is the smallest primitive root mod 40487 but not one mod 40487^2, so it gets lifted to 5 + 40487
+        let root = U64CoprimeMod::<{ 40_487 * 40_487 }>::primitive_root().unwrap();
+        assert_eq!(root.value(), 40_492);
+        assert!(root.is_primitive_root());
+        assert!(U64CoprimeMod::<{ 2 * 3u64.pow(20) }>::primitive_root().unwrap().is_primitive_root());
+    }
+
+    #[test]
+    fn test_cyclic_decomposition() {
+        let factors = U32CoprimeMod::<360>::cyclic_decomposition();
+        let orders: Vec<u128> = factors.iter().map(|&(_, order)| order).collect();
+        assert_eq!(orders, vec![2, 2, 6, 4]);
+        for &(g, order) in &factors {
+            assert_eq!(g.multiplicative_order(), Some(order));
+        }
+
+        // Products of powers of the generators reach every unit exactly once
+        let mut units = HashSet::from([U32CoprimeMod::<360>::identity()]);
+        for &(g, order) in &factors {
+            units = units.iter().flat_map(|&u| (0..order).map(move |e| u * pow::<MUL, _>(g, e))).collect();
+        }
+        assert_eq!(units.len() as u128, U32CoprimeMod::<360>::unit_group_order());
+        assert_eq!(units.len(), 96);
+    }
+
+    #[test]
+    fn test_u128_units() {
+        // 2^127 - 1 is prime, and 2 has order 127 since 2^127 = 1
+        const P: u128 = (1 << 127) - 1;
+        assert_eq!(U128Field::<P>(2).multiplicative_order(), Some(127));
+        assert!(U128Field::<P>::primitive_root().unwrap().is_primitive_root());
+
+        // Past u64, odd prime powers still have cyclic unit groups
+        const M: u128 = 3u128.pow(80);
+        let root = U128CoprimeMod::<M>::primitive_root().unwrap();
+        assert!(root.is_primitive_root());
+        assert_eq!(root.multiplicative_order(), Some(2 * 3u128.pow(79)));
+
+        let orders: Vec<u128> = U128CoprimeMod::<{ 1 << 100 }>::cyclic_decomposition().iter().map(|&(_, order)| order).collect();
+        assert_eq!(orders, vec![2, 1 << 98]);
+        assert_eq!(U128CoprimeMod::<{ 1 << 100 }>::primitive_root(), None);
+    }
+}